@@ -7,20 +7,32 @@
 use std::{
     cell::{Cell, RefCell},
     cmp,
-    collections::VecDeque,
+    collections::{BTreeMap, VecDeque},
     mem,
     ops::Range,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use collections::HashMap;
 use engine_traits::{KvEngine, RaftEngine, RAFT_LOG_MULTI_GET_CNT};
 use fail::fail_point;
 use kvproto::raft_serverpb::{RaftApplyState, RaftLocalState};
+// Assumes `lz4-sys` is already a workspace dependency, as it is for the
+// compression paths in the engine crates; see `lz4_compress`/`lz4_decompress`
+// below for the only place this module touches it.
+use lz4_sys::{LZ4_compressBound, LZ4_compress_default, LZ4_decompress_safe};
 use protobuf::Message;
 use raft::{prelude::*, util::limit_size, GetEntriesContext, StorageError};
 use tikv_alloc::TraceEvent;
 use tikv_util::{debug, info, worker::Scheduler};
+// Assumes `xxhash-rust` (the `xxh3` feature) is already a workspace
+// dependency, added alongside `lz4-sys`; see `chunk_hash` below for the
+// only place this module touches it.
+use xxhash_rust::xxh3;
 
 use super::{metrics::*, peer_storage::storage_error, WriteTask, MEMTRACE_ENTRY_CACHE};
 use crate::{bytes_capacity, store::worker::RaftlogFetchTask};
@@ -31,6 +43,11 @@ const ENTRY_MEM_SIZE: usize = mem::size_of::<Entry>();
 
 pub const MAX_INIT_ENTRY_COUNT: usize = 1024;
 
+/// Bound on [`TermCache`]'s ring -- generous enough to cover the recently
+/// appended indices a leader's conflict-resolution term probes actually
+/// target, without holding onto `(index, term)` pairs indefinitely.
+const TERM_CACHE_CAPACITY: usize = 1024;
+
 #[inline]
 pub fn first_index(state: &RaftApplyState) -> u64 {
     state.get_truncated_state().get_index() + 1
@@ -68,6 +85,256 @@ impl CachedEntries {
     }
 }
 
+/// Bookkeeping for one persisted entry whose `data`/`context` have been
+/// LZ4-compressed in place to shrink it while it sits in cache. The
+/// pre-compression `data_len`/`context_len` need to survive alongside the
+/// compressed bytes -- `LZ4_decompress_safe` needs an exact-sized output
+/// buffer and won't infer it from the compressed stream itself. `raw_size`
+/// is the entry's real `compute_size()` before compression: raft peers size
+/// their `max_size` budgets against real message sizes, so anything that
+/// walks the cache for that purpose (see `EntryCache::fetch_entries_to`)
+/// must keep using this instead of `compute_size()` on the now-shrunken,
+/// in-place-compressed entry.
+struct CompressedMeta {
+    data_len: u32,
+    context_len: u32,
+    raw_size: u32,
+}
+
+/// Safe wrappers around `lz4_sys`'s block-compression API. Empty inputs are
+/// special-cased rather than handed to LZ4, since `LZ4_compress_default`
+/// doesn't promise a meaningful result for a zero-length source.
+fn lz4_compress(src: &[u8]) -> Vec<u8> {
+    if src.is_empty() {
+        return Vec::new();
+    }
+    unsafe {
+        let bound = LZ4_compressBound(src.len() as i32);
+        let mut dst = vec![0u8; bound as usize];
+        let written = LZ4_compress_default(
+            src.as_ptr() as *const _,
+            dst.as_mut_ptr() as *mut _,
+            src.len() as i32,
+            bound,
+        );
+        assert!(written > 0, "LZ4_compress_default failed");
+        dst.truncate(written as usize);
+        dst
+    }
+}
+
+fn lz4_decompress(src: &[u8], original_len: usize) -> Vec<u8> {
+    if original_len == 0 {
+        return Vec::new();
+    }
+    unsafe {
+        let mut dst = vec![0u8; original_len];
+        let written = LZ4_decompress_safe(
+            src.as_ptr() as *const _,
+            dst.as_mut_ptr() as *mut _,
+            src.len() as i32,
+            original_len as i32,
+        );
+        assert_eq!(written as usize, original_len, "LZ4_decompress_safe failed");
+        dst
+    }
+}
+
+/// Content-defined chunking parameters, FastCDC-style normalized chunking:
+/// a stricter mask is used below `CDC_AVG_CHUNK_SIZE` (biasing boundaries
+/// later, toward the average) and a looser one above it (biasing them
+/// earlier), so the resulting chunk-size distribution clusters around the
+/// average instead of following FastCDC's un-normalized exponential tail.
+const CDC_MIN_CHUNK_SIZE: usize = 2 * 1024;
+const CDC_AVG_CHUNK_SIZE: usize = 8 * 1024;
+const CDC_MAX_CHUNK_SIZE: usize = 64 * 1024;
+// 14 low bits must be zero (prob. ~1/16384): stricter than `CDC_MASK_LARGE`,
+// used for the run below `CDC_AVG_CHUNK_SIZE` so cuts there are rarer.
+const CDC_MASK_SMALL: u64 = (1 << 14) - 1;
+// 11 low bits must be zero (prob. ~1/2048): looser, used past the average
+// so a chunk overshooting it gets pulled back down quickly.
+const CDC_MASK_LARGE: u64 = (1 << 11) - 1;
+
+/// Per-byte multipliers for the gear rolling hash `cdc_chunk_boundaries`
+/// uses to find content-defined cut points: each new byte's table entry
+/// swamps out the contribution of bytes more than 64 shifts in the past,
+/// giving the hash a bounded effective window without maintaining one
+/// explicitly. Values are arbitrary but fixed -- generated once and frozen,
+/// same spirit as any textbook gear/FastCDC table.
+#[rustfmt::skip]
+static CDC_GEAR: [u64; 256] = [
+    0xd00ab5cd79cf7bb8, 0xc11ba32beee1222d, 0x56145850b6d398cf, 0x56504a96a717817b,
+    0x2d0fda0ae380ba9b, 0xb989778c4c8fdd7d, 0x674f8dd03dd58bab, 0x8f7186c62f596a87,
+    0x082cd83d1732dd51, 0xee043f3c2227588a, 0x002edc0bbe458b3d, 0x610c3427a8a5b8ab,
+    0x5ebe9b18b96e439f, 0x3025da72a1daee36, 0xf1c23c3d653d20d1, 0x1e4353d34310ed69,
+    0x198cbf62278ca27c, 0x8166b74729d14a4b, 0xa9ac3c7fa50d45cc, 0x30dc7c6fd9a98be5,
+    0x8783866acee23e43, 0x32a9be44c0ac06ce, 0x0c2b52971b142017, 0xd9dcb21a649805f7,
+    0x3a0363bcb37f80d6, 0x481b26ac8a834845, 0x730019ebcf71fe81, 0xbe0516105ee29d61,
+    0x98ed92155a138dfe, 0x0eb08b31e0e1fc8a, 0x599e62086a0b45f0, 0x7245e1b759591237,
+    0x1c2a490d106c237c, 0x17961aba145dbdff, 0xae39529ce8eaa0bc, 0x4f07737ff2d534f5,
+    0x754fc2b93c2d26f7, 0x42118e7c487a8c55, 0x1a78f82299d65a76, 0x27485ed7a64de836,
+    0xe8145bfdfda8ed85, 0x996a969f0515839e, 0x9a353a86b8af374d, 0xd661aa3d1b502790,
+    0x95df47ed99f0dcb4, 0xa44412622d56d995, 0x7f37d160dbb84179, 0xf42d568fa0fd23a7,
+    0x399ee1cad5dd40e4, 0x7d3b9fd24a401990, 0xc1b4c55c1329315f, 0x032ee33c57e43d05,
+    0xe0957097a1110f8c, 0xaf0d03da3f5feabe, 0xe7b122696d0d8ad5, 0xe8bffd98ef257cd2,
+    0x2857d170540803f7, 0x98917d6d349603f1, 0x0723d8e8031c7992, 0x7fe6ceeba8e5c1cd,
+    0x60666f1980ebbd1c, 0x9843b25752048400, 0xfdae7075f2c0782c, 0xea5cc256096ce969,
+    0x1d8219af4bbd528e, 0x2e38573e598f55a9, 0x6209c1f9a3c4165f, 0x5ba649a392bd2c58,
+    0x30578061d4a7b88e, 0x3c1f9597ae550e48, 0xc4ac7af709c0b503, 0xefc621e4a0eb1896,
+    0x6da93fece787a2dc, 0x5d4fb4ff08dc5029, 0x6ede3211f4bbe2a0, 0xd97eb85bf444810b,
+    0xc9ae648ed82c53f9, 0xd81d00f2a459e4aa, 0x4c094cb248233004, 0xbe060ba6f5300b53,
+    0x15884dd30a44da23, 0xc29efb949459c9ce, 0x95f02a853a7fdbdf, 0x8cee292348687ffa,
+    0xbc49d000761f1fa3, 0xf80b3c05d5cd9737, 0x67acc48c9d6ccc55, 0xee855deefcada65e,
+    0xec926e08b5e37ce6, 0x99cda92620dde2a2, 0x2c596ca5f02fd22b, 0x0beb2a2be7aa8c70,
+    0xb2557bc8d43cda51, 0x8b62f32e0c39dda7, 0x8821007e5e989ad3, 0xfa32868d13dbf77c,
+    0xac9e4e6a7b122d7d, 0x46e23b7b9e2dacc0, 0x8e766cad2dbd1f6b, 0x085b39a7656ce539,
+    0xd63e821a28015d12, 0x39c8dece177d76b8, 0xb4cb5026199d4cb5, 0x8ae561ba8242dc1a,
+    0x1657021ea585ee81, 0xd4901a064aee4c5e, 0xd285d05ee83f1110, 0x3fbb665933d8709e,
+    0x1b036fe13a077a57, 0xe61b2e9b8592d38f, 0xf51148b0932fe5a4, 0xe5c883ad2b6f298f,
+    0x49f34240b6cee2bc, 0x1549e8fb24ceed25, 0xfc83db74ec785e7f, 0x556847a8c64f5fe1,
+    0x5fdd5ef79fdaf2fd, 0x161823dc54ca0ef0, 0x00c90d0a1e7659ed, 0xc6202c1b17eb2c78,
+    0xfe4efdc01ad5cf12, 0xb0bb21e5e7dc3ac8, 0x6519ab7a6c727b95, 0x92dc4f696e55fc88,
+    0xf20e03f15a619ac1, 0x351dcdda53bb871d, 0xc90aa848c7b9124a, 0xf1b4510b74dc77db,
+    0xc51f6d901ce6941b, 0x7c602f7993be6ff1, 0x10d460078122a118, 0x1f9b621f65fa1e57,
+    0x7887287feb045d0d, 0x2f719d09aaf75d21, 0x5d0710a9130a3afa, 0xf21f9cde344cb4ef,
+    0x15b94db4b38646fb, 0xfc2994c73b293da4, 0xe7fa4619067244b1, 0x1c2388e81cdb1f0b,
+    0x6049e01e279fbaae, 0x2938240d8e53babb, 0x6670af98efc15047, 0x2c7a5aec5845d22a,
+    0x3897f86a0b63892a, 0xc731ef844b47d89b, 0xbd298581105b3557, 0xd728c98703bee992,
+    0xed1527f89fac7502, 0xa4cd74dc6cd59b6f, 0x40baa962ddfb8ec9, 0x9f4e56287e790505,
+    0xd0e4f5f3430b428d, 0x5121670559fbcea5, 0xf32af772732fddaf, 0xb84286d74b8fbd8a,
+    0x3e6f0e4e46eb81dd, 0x9419f3723ede8a02, 0xe0eea239accc0dce, 0x3c5b8a5f54c5ec1d,
+    0x7b5d398ed8dacaeb, 0x9a59af7416503881, 0x0bd4acb152d50392, 0x3f4d3f66b60c4e2c,
+    0x6f958def3d1e7759, 0x8b28f0400c463ce1, 0x08897b980d7ddf1b, 0x1919edea4e6950d4,
+    0xa2080e8b457d31ef, 0x07146b223ae91ac5, 0x6b58f5c20d9281cb, 0xcd5ac7cc1c47b101,
+    0x83105826d8a744ba, 0x2be00eae75470af3, 0x8581dff716258819, 0xc331f8deda01d14c,
+    0xca862238efed2f0e, 0x86587da580f607c9, 0x3f643db95e73ca94, 0xef5175a3674cd148,
+    0xd0bdb614e8c1fb9d, 0xbd7c892f1f28e283, 0xde1d9344c4915756, 0xa801848d018a7df8,
+    0x5fd0cc88a3ebf3a7, 0x2473e957fb33efa0, 0xef60f941738cb91d, 0x0aa35c26d2991c61,
+    0x9b91dc251ba4ae71, 0x537576bb428358b5, 0x4294e7545cc12586, 0xd3c51f16a0ae5682,
+    0xac8014e760c8dbc9, 0xe40278c6fd9c3a8c, 0xedf37b7e0718d80c, 0x4bcdccdf91e5b059,
+    0x724c33244db4da59, 0x819385d1de0dcf66, 0xb670871bf345dc3d, 0x466c4818050afbe6,
+    0x2859e16798f3cdd5, 0x398f893a73f1b166, 0x6f4c7e04b668cc41, 0x3ba987136d45e0c5,
+    0xdbab3f435a4d3921, 0xb51dedb4364d7e98, 0xfd87aa1ab32631b7, 0x6384e6372a7cf40c,
+    0xf983bafd303f4ad0, 0x490c76f8208a1641, 0x36bdc0dffc29dd4f, 0xff557659eee4bc3d,
+    0xd27179c86e739fdf, 0xf490f16c17526323, 0x0b35d2f2bd469194, 0x9743355c71d70e0d,
+    0xbb90dbbdb3820c06, 0xbbb46bb7e25b7dd7, 0xa678ac178f543570, 0x79fff19bd034e56b,
+    0x09b554c4bd321850, 0xfd7d79d9f7b1cc97, 0x46ec5bb34a47e77a, 0x4df0905cd29e6d7c,
+    0x27d1b9de2b5baa55, 0x15badd25a2f754f7, 0xdebd8f48f65bc490, 0x8d5ffe23e13ef50d,
+    0xd3927967ab6502df, 0x22172404c90591dc, 0x3258ab8e20f434b6, 0x6c0f033581c7798d,
+    0x06970c86bc380d0e, 0xde8578f7bacb66e0, 0x1b03aaa6d4979892, 0xd4d0717ae39c527e,
+    0x9eb77b2197a4f838, 0xa6ef8b25716ffac0, 0x90b3b3b48f9f6429, 0xf494c93ebe7e659e,
+    0xf834001d9cabcada, 0xf91a7a781fcb8f12, 0x1222bb43785a568a, 0x1f45de5bee7b5a0d,
+    0x67e34092f0cc2a8b, 0x22d40b1c4a2f597c, 0x62fac6e4ff1cb62c, 0x71d405b08f47d5d5,
+    0x415739faf522705c, 0x5d88f3cbcd4a551e, 0xa1c20b92b8309362, 0x4d41cdcd9c05b13d,
+    0xe6d37afdf56a0a9f, 0xca48f3fe8436e767, 0x7b48fe1b7b531fa1, 0xf526a0b0a00b37bb,
+];
+
+/// Cuts `data` into content-defined chunk boundaries using a gear-based
+/// rolling hash with FastCDC normalized chunking, returning each chunk's
+/// exclusive end offset in ascending order. The last boundary always equals
+/// `data.len()`. Empty input yields no boundaries.
+fn cdc_chunk_boundaries(data: &[u8]) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut start = 0usize;
+    while start < data.len() {
+        let remaining = data.len() - start;
+        let max_len = cmp::min(remaining, CDC_MAX_CHUNK_SIZE);
+        if max_len <= CDC_MIN_CHUNK_SIZE {
+            start += max_len;
+            boundaries.push(start);
+            continue;
+        }
+        let mut hash: u64 = 0;
+        let mut cut = None;
+        for (i, &b) in data[start..start + max_len].iter().enumerate() {
+            hash = (hash << 1).wrapping_add(CDC_GEAR[b as usize]);
+            let pos = i + 1;
+            if pos < CDC_MIN_CHUNK_SIZE {
+                continue;
+            }
+            let mask = if pos < CDC_AVG_CHUNK_SIZE {
+                CDC_MASK_SMALL
+            } else {
+                CDC_MASK_LARGE
+            };
+            if hash & mask == 0 {
+                cut = Some(pos);
+                break;
+            }
+        }
+        start += cut.unwrap_or(max_len);
+        boundaries.push(start);
+    }
+    boundaries
+}
+
+/// Hashes one chunk's bytes for pool lookup.
+fn chunk_hash(data: &[u8]) -> u64 {
+    xxh3::xxh3_64(data)
+}
+
+/// Bookkeeping for one cached entry whose `data` has been cut into
+/// content-defined chunks and handed to `ChunkPool` instead of being kept
+/// inline -- the matching `cache` slot's `Entry.data` is empty, and the
+/// real payload is the concatenation of `chunks`, looked up by hash.
+/// `raw_size` is the entry's real `compute_size()` before chunking, for the
+/// same reason `CompressedMeta::raw_size` exists: `max_size` budgeting must
+/// see real message sizes, not however small the deduplicated form is.
+struct ChunkedMeta {
+    raw_size: u32,
+    chunks: Vec<u64>,
+}
+
+/// Content-addressed, refcounted store for chunks produced by
+/// `cdc_chunk_boundaries`. Identical chunks -- common across entries in
+/// write-heavy regions that repeat or bulk-load similar KV batches --
+/// collapse to a single stored copy here regardless of which entries
+/// reference them.
+#[derive(Default)]
+struct ChunkPool {
+    chunks: HashMap<u64, (Vec<u8>, u32)>,
+}
+
+impl ChunkPool {
+    /// Adds a reference to `data`'s chunk, storing it if this is the first
+    /// reference. Returns `(hash, mem_size_change)`; `mem_size_change` is
+    /// only nonzero (the chunk's byte length) the first time a given hash
+    /// is seen.
+    fn insert(&mut self, data: &[u8]) -> (u64, i64) {
+        let hash = chunk_hash(data);
+        if let Some(entry) = self.chunks.get_mut(&hash) {
+            entry.1 += 1;
+            return (hash, 0);
+        }
+        let len = data.len() as i64;
+        self.chunks.insert(hash, (data.to_vec(), 1));
+        (hash, len)
+    }
+
+    /// Drops one reference to `hash`'s chunk, freeing and returning its
+    /// negated byte length once the refcount reaches zero; `0` otherwise
+    /// (including if `hash` isn't tracked, which shouldn't happen).
+    fn release(&mut self, hash: u64) -> i64 {
+        let drop_it = match self.chunks.get_mut(&hash) {
+            Some(entry) => {
+                entry.1 -= 1;
+                entry.1 == 0
+            }
+            None => return 0,
+        };
+        if drop_it {
+            let (bytes, _) = self.chunks.remove(&hash).unwrap();
+            -(bytes.len() as i64)
+        } else {
+            0
+        }
+    }
+
+    fn get(&self, hash: u64) -> Option<&[u8]> {
+        self.chunks.get(&hash).map(|(bytes, _)| bytes.as_slice())
+    }
+}
+
 struct EntryCache {
     // The last index of persisted entry.
     // It should be equal to `RaftLog::persisted`.
@@ -76,6 +343,32 @@ struct EntryCache {
     trace: VecDeque<CachedEntries>,
     hit: Cell<u64>,
     miss: Cell<u64>,
+    // Entries at or below `persisted` get their `data`/`context` replaced
+    // with an LZ4-compressed stand-in once they clear `compress_threshold`
+    // bytes -- raft-rs only reads persisted entries from this cache for the
+    // occasional slow follower, not the hot MsgAppend path, so the space
+    // saving is usually worth the decompress-on-read cost. `None` disables
+    // the feature entirely (the default).
+    compress_threshold: Option<u64>,
+    // Keyed by index; presence means that entry's `data`/`context` in
+    // `cache` currently hold compressed bytes, not the original payload.
+    compressed: HashMap<u64, CompressedMeta>,
+    decompress_count: Cell<u64>,
+    compress_original_bytes: Cell<u64>,
+    compress_bytes: Cell<u64>,
+    // Entries at or below `persisted` whose `data` clears `chunk_threshold`
+    // bytes are cut into content-defined chunks and deduplicated against
+    // `chunk_pool` instead of being LZ4-compressed -- chunking wins out over
+    // `compress_threshold` when both are set, since it also collapses
+    // payloads that repeat *across* entries, not just within one. `None`
+    // disables the feature entirely (the default).
+    chunk_threshold: Option<u64>,
+    // Keyed by index; presence means that entry's `data` in `cache` is
+    // empty and its real payload is the concatenation of these chunk
+    // hashes, looked up in `chunk_pool`.
+    chunked: HashMap<u64, ChunkedMeta>,
+    chunk_pool: ChunkPool,
+    chunk_dedup_hits: Cell<u64>,
     #[cfg(test)]
     size_change_cb: Option<Box<dyn Fn(i64) + Send + 'static>>,
 }
@@ -85,6 +378,37 @@ impl EntryCache {
         self.cache.front().map(|e| e.get_index())
     }
 
+    /// Translates absolute log index `idx` into an offset into `self.cache`,
+    /// or `None` if `idx` falls outside `[first_index, last_index]` --
+    /// including the empty-cache case. `entry`, `chunk_if_eligible` and
+    /// `compress_if_eligible` index `self.cache` through this, so a stale or
+    /// out-of-range index there yields `None` rather than an out-of-bounds
+    /// `self.cache[..]`. `fetch_entries_to`, `append_impl`, `compact_to` and
+    /// `persisted_segment` compute their offsets from the first index
+    /// themselves and do not go through this helper.
+    fn index_to_offset(&self, idx: u64) -> Option<usize> {
+        let first = self.first_index()?;
+        let offset = idx.checked_sub(first)? as usize;
+        if offset >= self.cache.len() {
+            return None;
+        }
+        Some(offset)
+    }
+
+    /// Debug-only sanity check that `self.cache` still holds a contiguous
+    /// run of indexes after a mutation -- the invariant every offset
+    /// computed via [`Self::index_to_offset`] relies on.
+    fn debug_assert_contiguous(&self) {
+        debug_assert!(
+            self.cache
+                .iter()
+                .zip(self.cache.iter().skip(1))
+                .all(|(a, b)| b.get_index() == a.get_index() + 1),
+            "entry cache indexes are not contiguous: {:?}",
+            self.cache.iter().map(|e| e.get_index()).collect::<Vec<_>>()
+        );
+    }
+
     fn fetch_entries_to(
         &self,
         begin: u64,
@@ -108,7 +432,19 @@ impl EntryCache {
             .take_while(|e| {
                 let cur_idx = end_idx as u64 + cache_low;
                 assert_eq!(e.get_index(), cur_idx);
-                let m = u64::from(e.compute_size());
+                // A compressed entry's `compute_size()` reflects the shrunken
+                // in-cache bytes, not what raft actually receives once
+                // `decompress_in_place` restores it below -- budget against
+                // the real, pre-compression size instead so `max_size` means
+                // the same thing regardless of whether this range happens to
+                // be compressed.
+                let m = match self.compressed.get(&cur_idx) {
+                    Some(meta) => u64::from(meta.raw_size),
+                    None => match self.chunked.get(&cur_idx) {
+                        Some(meta) => u64::from(meta.raw_size),
+                        None => u64::from(e.compute_size()),
+                    },
+                };
                 fetched_size += m;
                 if fetched_size == m {
                     end_idx += 1;
@@ -124,9 +460,16 @@ impl EntryCache {
         // Cache either is empty or contains latest log. Hence we don't need to fetch
         // log from rocksdb anymore.
         assert!(end_idx == limit_idx || fetched_size > max_size);
+        let before_len = ents.len();
         let (first, second) = tikv_util::slices_in_range(&self.cache, start_idx, end_idx);
         ents.extend_from_slice(first);
         ents.extend_from_slice(second);
+        if !self.compressed.is_empty() || !self.chunked.is_empty() {
+            for e in &mut ents[before_len..] {
+                self.decompress_in_place(e);
+                self.reassemble_chunks_in_place(e);
+            }
+        }
     }
 
     fn append(&mut self, region_id: u64, peer_id: u64, entries: &[Entry]) {
@@ -155,6 +498,13 @@ impl EntryCache {
                 for e in self.cache.drain(truncate_to..) {
                     mem_size_change -=
                         (bytes_capacity(&e.data) + bytes_capacity(&e.context)) as i64;
+                    self.compressed.remove(&e.get_index());
+                    if let Some(meta) = self.chunked.remove(&e.get_index()) {
+                        mem_size_change -= (meta.chunks.len() * mem::size_of::<u64>()) as i64;
+                        for hash in meta.chunks {
+                            mem_size_change += self.chunk_pool.release(hash);
+                        }
+                    }
                 }
                 if let Some(cached) = self.trace.back() {
                     // Only committed entries can be traced, and only uncommitted entries
@@ -179,20 +529,209 @@ impl EntryCache {
         // cache if it's not persisted to raft db because the raft-rs may need to read
         // entries.(e.g. leader sends MsgAppend to followers)
 
+        // Entries can arrive here already persisted (e.g. on recovery), so
+        // give them the same compression/chunking chance `update_persisted`
+        // gives entries that cross the persisted line after being cached.
+        if self.chunk_threshold.is_some() || self.compress_threshold.is_some() {
+            for e in entries {
+                mem_size_change += self.apply_persisted_transform(e.get_index());
+            }
+        }
+
+        self.debug_assert_contiguous();
         mem_size_change
     }
 
+    /// Shrinks cached entry `idx`'s footprint once it's persisted, trying
+    /// content-defined chunk dedup first and falling back to whole-entry
+    /// LZ4 compression if chunking isn't enabled or didn't apply -- the two
+    /// are mutually exclusive per entry since both rewrite `data` in place.
+    /// Chunking is preferred because it also collapses payloads that repeat
+    /// *across* entries, not just within one.
+    fn apply_persisted_transform(&mut self, idx: u64) -> i64 {
+        if self.chunk_threshold.is_some() {
+            let delta = self.chunk_if_eligible(idx);
+            if self.chunked.contains_key(&idx) {
+                return delta;
+            }
+        }
+        self.compress_if_eligible(idx)
+    }
+
+    /// LZ4-decompresses `e` in place if it was cached under its compressed
+    /// form, leaving it untouched otherwise. The common case
+    /// costs one `HashMap` lookup.
+    fn decompress_in_place(&self, e: &mut Entry) {
+        if let Some(meta) = self.compressed.get(&e.get_index()) {
+            self.decompress_count.update(|c| c + 1);
+            e.data = lz4_decompress(&e.data, meta.data_len as usize).into();
+            e.context = lz4_decompress(&e.context, meta.context_len as usize).into();
+        }
+    }
+
+    /// Reassembles `e`'s `data` from `chunk_pool` in place if it was cached
+    /// in chunked form, leaving it untouched otherwise. A chunk missing from
+    /// `chunk_pool` means the dedup bookkeeping in `append_impl`/`compact_to`
+    /// has a bug -- silently substituting empty bytes would hand raft a
+    /// corrupted, truncated entry instead, so this panics the same way
+    /// `decompress_in_place`'s `lz4_decompress` does on a bad payload.
+    fn reassemble_chunks_in_place(&self, e: &mut Entry) {
+        if let Some(meta) = self.chunked.get(&e.get_index()) {
+            self.chunk_dedup_hits.update(|c| c + 1);
+            let mut data = Vec::with_capacity(meta.raw_size as usize);
+            for hash in &meta.chunks {
+                let chunk = self.chunk_pool.get(*hash).unwrap_or_else(|| {
+                    panic!(
+                        "entry cache chunk {} missing from chunk_pool for index {}",
+                        hash,
+                        e.get_index()
+                    )
+                });
+                data.extend_from_slice(chunk);
+            }
+            e.data = data.into();
+        }
+    }
+
+    /// Tries to cut cached entry `idx`'s `data` into content-defined chunks
+    /// and dedup them against `chunk_pool`. No-ops unless chunking is
+    /// enabled, `idx` is actually cached, persisted, not already chunked or
+    /// compressed, and at or above `chunk_threshold` bytes. Returns the
+    /// resulting memory-size delta (the net of unique chunk bytes added to
+    /// the pool, chunk-id overhead, and the inline bytes dropped from `e`).
+    fn chunk_if_eligible(&mut self, idx: u64) -> i64 {
+        let threshold = match self.chunk_threshold {
+            Some(t) => t,
+            None => return 0,
+        };
+        if idx > self.persisted
+            || self.chunked.contains_key(&idx)
+            || self.compressed.contains_key(&idx)
+        {
+            return 0;
+        }
+        let cache_idx = match self.index_to_offset(idx) {
+            Some(i) => i,
+            None => return 0,
+        };
+        let data_len = self.cache[cache_idx].data.len();
+        if (data_len as u64) < threshold {
+            return 0;
+        }
+        let raw_size = self.cache[cache_idx].compute_size();
+        let data = self.cache[cache_idx].data.clone();
+        let mut chunks = Vec::new();
+        let mut pool_growth = 0i64;
+        let mut start = 0usize;
+        for end in cdc_chunk_boundaries(&data) {
+            let (hash, growth) = self.chunk_pool.insert(&data[start..end]);
+            pool_growth += growth;
+            chunks.push(hash);
+            start = end;
+        }
+        let chunk_id_overhead = (chunks.len() * mem::size_of::<u64>()) as i64;
+        let before = data_len as i64;
+        let after = pool_growth + chunk_id_overhead;
+        self.cache[cache_idx].data = Vec::new().into();
+        self.chunked.insert(idx, ChunkedMeta { raw_size, chunks });
+        after - before
+    }
+
+    /// Tries to LZ4-compress cached entry `idx`'s `data`/`context` in
+    /// place. No-ops unless compression is enabled, `idx` is actually
+    /// cached, persisted, not already compressed or chunked, and at or
+    /// above `compress_threshold` bytes. Returns the resulting memory-size
+    /// delta, compressed minus original bytes; it is positive when LZ4
+    /// output is larger than an incompressible payload, which is stored
+    /// compressed all the same.
+    fn compress_if_eligible(&mut self, idx: u64) -> i64 {
+        let threshold = match self.compress_threshold {
+            Some(t) => t,
+            None => return 0,
+        };
+        if idx > self.persisted
+            || self.compressed.contains_key(&idx)
+            || self.chunked.contains_key(&idx)
+        {
+            return 0;
+        }
+        let cache_idx = match self.index_to_offset(idx) {
+            Some(i) => i,
+            None => return 0,
+        };
+        let e = &mut self.cache[cache_idx];
+        let data_len = e.data.len();
+        let context_len = e.context.len();
+        if ((data_len + context_len) as u64) < threshold {
+            return 0;
+        }
+        let raw_size = e.compute_size();
+        let compressed_data = lz4_compress(&e.data);
+        let compressed_context = lz4_compress(&e.context);
+        let before = (data_len + context_len) as i64;
+        let after = (compressed_data.len() + compressed_context.len()) as i64;
+        e.data = compressed_data.into();
+        e.context = compressed_context.into();
+        self.compressed.insert(
+            idx,
+            CompressedMeta {
+                data_len: data_len as u32,
+                context_len: context_len as u32,
+                raw_size,
+            },
+        );
+        self.compress_original_bytes.update(|v| v + before as u64);
+        self.compress_bytes.update(|v| v + after as u64);
+        after - before
+    }
+
+    // Returns a borrowed reference rather than decompressing/reassembling a
+    // to-be-cloned copy: `term()`, the only non-test caller, only ever reads
+    // `get_term()`, which `compress_threshold`/`chunk_threshold` never touch
+    // (they replace `data`/`context`, not `term`/`index`). A caller that
+    // needs the real, possibly-compressed-or-chunked `data` back should go
+    // through `fetch_entries_to` instead, which does the decompress/
+    // reassemble pass on its own owned copies.
     pub fn entry(&self, idx: u64) -> Option<&Entry> {
-        let cache_low = self.cache.front()?.get_index();
-        if idx >= cache_low {
-            Some(&self.cache[(idx - cache_low) as usize])
-        } else {
-            None
+        let offset = self.index_to_offset(idx)?;
+        Some(&self.cache[offset])
+    }
+
+    /// Prepends `entries` to the front of the cache, used to warm it with a
+    /// landed async-fetch read-ahead result for the range just below what's
+    /// currently cached. Only splices them in when `entries` is exactly
+    /// contiguous with the current front (ends at `first_index() - 1`);
+    /// otherwise this is a silent no-op, since a hole at the front would be
+    /// just as invalid as the hole `append_impl` guards against at the
+    /// back. Returns the number of entries actually prepended.
+    fn prepend(&mut self, entries: &[Entry]) -> usize {
+        if entries.is_empty() {
+            return 0;
         }
+        let first = match self.first_index() {
+            Some(first) => first,
+            None => return 0,
+        };
+        if entries.last().unwrap().get_index() + 1 != first {
+            return 0;
+        }
+
+        let mut mem_size_change = 0;
+        let old_capacity = self.cache.capacity();
+        for e in entries.iter().rev() {
+            self.cache.push_front(e.to_owned());
+            mem_size_change += (bytes_capacity(&e.data) + bytes_capacity(&e.context)) as i64;
+        }
+        let new_capacity = self.cache.capacity();
+        mem_size_change += Self::cache_vec_mem_size_change(new_capacity, old_capacity);
+        self.flush_mem_size_change(mem_size_change);
+        self.debug_assert_contiguous();
+        entries.len()
     }
 
     /// Compact all entries whose indexes are less than `idx`.
-    pub fn compact_to(&mut self, mut idx: u64) -> u64 {
+    /// Returns `(bytes_reclaimed, entries_reclaimed)`.
+    pub fn compact_to(&mut self, mut idx: u64) -> (u64, usize) {
         if idx > self.persisted + 1 {
             // Only the persisted entries can be compacted
             idx = self.persisted + 1;
@@ -225,21 +764,32 @@ impl EntryCache {
         if cache_first_idx >= idx {
             self.flush_mem_size_change(mem_size_change);
             assert!(mem_size_change <= 0);
-            return -mem_size_change as u64;
+            return (-mem_size_change as u64, 0);
         }
 
         let cache_last_idx = self.cache.back().unwrap().get_index();
         // Use `cache_last_idx + 1` to make sure cache can be cleared completely if
         // necessary.
         let compact_to = (cmp::min(cache_last_idx + 1, idx) - cache_first_idx) as usize;
+        let mut entries_reclaimed = 0usize;
         for e in self.cache.drain(..compact_to) {
-            mem_size_change -= (bytes_capacity(&e.data) + bytes_capacity(&e.context)) as i64
+            entries_reclaimed += 1;
+            mem_size_change -= (bytes_capacity(&e.data) + bytes_capacity(&e.context)) as i64;
+            let eidx = e.get_index();
+            self.compressed.remove(&eidx);
+            if let Some(meta) = self.chunked.remove(&eidx) {
+                mem_size_change -= (meta.chunks.len() * mem::size_of::<u64>()) as i64;
+                for hash in meta.chunks {
+                    mem_size_change += self.chunk_pool.release(hash);
+                }
+            }
         }
 
         mem_size_change += self.shrink_if_necessary();
         self.flush_mem_size_change(mem_size_change);
         assert!(mem_size_change <= 0);
-        -mem_size_change as u64
+        self.debug_assert_contiguous();
+        (-mem_size_change as u64, entries_reclaimed)
     }
 
     fn total_mem_size(&self) -> i64 {
@@ -250,7 +800,22 @@ impl EntryCache {
             .sum();
         let cache_vec_size = Self::cache_vec_mem_size_change(self.cache.capacity(), 0);
         let trace_vec_size = Self::trace_vec_mem_size_change(self.trace.capacity(), 0);
-        data_size + cache_vec_size + trace_vec_size
+        // Chunked entries keep their real payload in `chunk_pool`, not in
+        // `cache`, plus a per-entry chunk-id list -- both need to be part
+        // of the total so `Drop`'s `-total_mem_size()` fully unwinds what
+        // `chunk_if_eligible` added incrementally.
+        let chunk_pool_size: i64 = self
+            .chunk_pool
+            .chunks
+            .values()
+            .map(|(b, _)| b.len() as i64)
+            .sum();
+        let chunk_id_overhead: i64 = self
+            .chunked
+            .values()
+            .map(|meta| (meta.chunks.len() * mem::size_of::<u64>()) as i64)
+            .sum();
+        data_size + cache_vec_size + trace_vec_size + chunk_pool_size + chunk_id_overhead
     }
 
     fn cache_vec_mem_size_change(new_capacity: usize, old_capacity: usize) -> i64 {
@@ -280,6 +845,22 @@ impl EntryCache {
         RAFT_ENTRY_FETCHES.hit.inc_by(hit);
         let miss = self.miss.replace(0);
         RAFT_ENTRY_FETCHES.miss.inc_by(miss);
+        let decompress = self.decompress_count.replace(0);
+        if decompress > 0 {
+            RAFT_ENTRY_FETCHES.decompress.inc_by(decompress);
+        }
+        // Running totals since compression was enabled, not reset each
+        // flush, so the ratio reflects overall effectiveness rather than
+        // just this tick's activity.
+        let original = self.compress_original_bytes.get();
+        if original > 0 {
+            let compressed = self.compress_bytes.get();
+            ENTRY_CACHE_COMPRESSION_RATIO_GAUGE.set((compressed * 1000 / original) as i64);
+        }
+        let chunk_hits = self.chunk_dedup_hits.replace(0);
+        if chunk_hits > 0 {
+            RAFT_ENTRY_FETCHES.chunk_reassemble.inc_by(chunk_hits);
+        }
     }
 
     #[inline]
@@ -336,7 +917,51 @@ impl EntryCache {
     }
 
     fn update_persisted(&mut self, persisted: u64) {
+        let old_persisted = self.persisted;
         self.persisted = persisted;
+        // Entries only become eligible for chunking/compression once they
+        // cross the persisted line, so this is the main place (besides
+        // entries that arrive already persisted in `append_impl`) that
+        // triggers it.
+        if (self.chunk_threshold.is_some() || self.compress_threshold.is_some())
+            && persisted > old_persisted
+        {
+            if let Some(first) = self.first_index() {
+                let last = self.cache.back().unwrap().get_index();
+                let start = cmp::max(first, old_persisted + 1);
+                let end = cmp::min(persisted, last);
+                let mut mem_size_change = 0;
+                for idx in start..=end {
+                    mem_size_change += self.apply_persisted_transform(idx);
+                }
+                if mem_size_change != 0 {
+                    self.flush_mem_size_change(mem_size_change);
+                }
+            }
+        }
+    }
+
+    /// The persisted -- and therefore safely evictable -- prefix of this
+    /// cache, as `[cache_first, persisted])`, and its byte size. `None` if
+    /// nothing currently in cache has been persisted yet. Used to keep an
+    /// [`EntryCacheManager`] registration in sync; never includes anything
+    /// past `persisted`, so a manager-driven eviction can't drop entries
+    /// raft-rs might still read for an in-flight MsgAppend.
+    fn persisted_segment(&self) -> Option<(Range<u64>, u64)> {
+        let first = self.first_index()?;
+        if self.persisted < first {
+            return None;
+        }
+        let last = self.cache.back().unwrap().get_index();
+        let end = cmp::min(self.persisted + 1, last + 1);
+        let end_idx = (end - first) as usize;
+        let bytes: u64 = self
+            .cache
+            .iter()
+            .take(end_idx)
+            .map(|e| (bytes_capacity(&e.data) + bytes_capacity(&e.context)) as u64)
+            .sum();
+        Some((first..end, bytes))
     }
 }
 
@@ -348,6 +973,15 @@ impl Default for EntryCache {
             trace: Default::default(),
             hit: Cell::new(0),
             miss: Cell::new(0),
+            compress_threshold: None,
+            compressed: HashMap::default(),
+            decompress_count: Cell::new(0),
+            compress_original_bytes: Cell::new(0),
+            compress_bytes: Cell::new(0),
+            chunk_threshold: None,
+            chunked: HashMap::default(),
+            chunk_pool: ChunkPool::default(),
+            chunk_dedup_hits: Cell::new(0),
             #[cfg(test)]
             size_change_cb: None,
         };
@@ -364,9 +998,68 @@ impl Drop for EntryCache {
     }
 }
 
+/// Ring buffer of recently-appended `(index, term)` pairs, kept alongside
+/// but independent of `EntryCache` so a leader's conflict-resolution term
+/// probes over recent indices stay cheap and in-memory even after
+/// `evict_entry_cache` has dropped the much heavier `Entry` bytes those
+/// indices used to carry. Entries are always appended/trimmed in increasing
+/// index order, same as the raft log itself, so a direct offset from the
+/// front covers the common case without a search.
+#[derive(Default)]
+struct TermCache {
+    terms: VecDeque<(u64, u64)>,
+}
+
+impl TermCache {
+    /// Records `entries`' `(index, term)` pairs, first dropping any
+    /// previously recorded pair at or past `entries[0]`'s index -- mirrors
+    /// the overwrite-on-conflict truncation `EntryCache::append_impl` does
+    /// for the same reason: an uncommitted suffix can be replaced wholesale.
+    fn append(&mut self, entries: &[Entry]) {
+        if entries.is_empty() {
+            return;
+        }
+        let first_index = entries[0].get_index();
+        while matches!(self.terms.back(), Some(&(idx, _)) if idx >= first_index) {
+            self.terms.pop_back();
+        }
+        for e in entries {
+            self.terms.push_back((e.get_index(), e.get_term()));
+        }
+        while self.terms.len() > TERM_CACHE_CAPACITY {
+            self.terms.pop_front();
+        }
+    }
+
+    /// Drops every pair for an index below `idx`, mirroring
+    /// `EntryCache::compact_to`'s prefix-compaction -- called only for a
+    /// compaction-driven shrink of the entry cache, not on a mere
+    /// memory-pressure eviction.
+    fn compact_to(&mut self, idx: u64) {
+        while matches!(self.terms.front(), Some(&(front, _)) if front < idx) {
+            self.terms.pop_front();
+        }
+    }
+
+    fn get(&self, idx: u64) -> Option<u64> {
+        let front = self.terms.front()?.0;
+        let offset = idx.checked_sub(front)? as usize;
+        self.terms
+            .get(offset)
+            .filter(|&&(i, _)| i == idx)
+            .map(|&(_, term)| term)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum RaftlogFetchState {
-    Fetching,
+    /// `high` is the exclusive end of the range this in-flight task was
+    /// scheduled to fetch (including any read-ahead extension), so a new
+    /// request covered by `[low, high)` can be recognized as already
+    /// satisfied in flight instead of scheduling a redundant fetch.
+    Fetching {
+        high: u64,
+    },
     Fetched(Box<RaftlogFetchResult>),
 }
 
@@ -392,6 +1085,18 @@ struct AsyncFetchStats {
     fallback_fetch: Cell<u64>,
     fetch_invalid: Cell<u64>,
     fetch_unused: Cell<u64>,
+    // Entries spliced into the cache from a read-ahead result rather than
+    // returned directly to the caller that triggered the fetch; see
+    // `EntryStorage::warm_cache_with_fetch_res`.
+    warmed_entries: Cell<u64>,
+    // A later `entries()` call was served straight from the cache thanks to
+    // the adaptive read-ahead window (see `ReadAheadState`) having already
+    // pulled that range in, instead of scheduling its own async fetch.
+    prefetch_hit: Cell<u64>,
+    // Bytes of a scheduled read-ahead surplus that landed but were dropped
+    // unused (see `ReadAheadState::charge_unused`) -- work spent reading
+    // ahead that nothing ever consumed.
+    prefetch_wasted_bytes: Cell<u64>,
 }
 
 impl AsyncFetchStats {
@@ -411,7 +1116,363 @@ impl AsyncFetchStats {
         RAFT_ENTRY_FETCHES
             .fetch_unused
             .inc_by(self.fetch_unused.replace(0));
+        RAFT_ENTRY_FETCHES
+            .warmed
+            .inc_by(self.warmed_entries.replace(0));
+        RAFT_ENTRY_FETCHES
+            .prefetch_hit
+            .inc_by(self.prefetch_hit.replace(0));
+        RAFT_ENTRY_FETCHES
+            .prefetch_wasted_bytes
+            .inc_by(self.prefetch_wasted_bytes.replace(0));
+    }
+}
+
+/// Number of consecutive sequential misses ([`ReadAheadState::observe`])
+/// required before the adaptive read-ahead window is allowed to grow. One
+/// miss is too weak a signal on its own (e.g. a one-off compaction-triggered
+/// refetch); two in a row with a non-decreasing `low` is a much stronger
+/// sign of an actual sequential catch-up scan.
+const SEQUENTIAL_RUN_THRESHOLD: u32 = 2;
+
+/// Smallest step the adaptive window grows by once a sequential pattern is
+/// confirmed, and the unit it doubles from thereafter.
+const MIN_ADAPTIVE_READ_AHEAD: u64 = 1024;
+
+/// Adaptive extension of [`EntryStorage::raftlog_fetch_read_ahead_size`]:
+/// widens the async-fetch read-ahead window further while a follower's
+/// misses look like a sequential catch-up scan (`low` advancing
+/// monotonically call over call), and backs the window off on any sign the
+/// extra prefetching isn't paying for itself -- a non-sequential miss, a
+/// scheduled read-ahead surplus landing unused (see
+/// [`EntryStorage::update_async_fetch_res`]), or
+/// [`EntryStorage::evict_entry_cache`] putting the cache under memory
+/// pressure. Everything here lives in `Cell`s because
+/// [`EntryStorage::async_fetch`], where the pattern is observed, only takes
+/// `&self`.
+#[derive(Default)]
+struct ReadAheadState {
+    // `low` of the previous genuinely-new async fetch (i.e. one that
+    // actually reached the scheduling tail of `async_fetch`), to detect a
+    // monotonic sequential-miss run.
+    last_low: Cell<Option<u64>>,
+    // Consecutive sequential misses observed so far.
+    run: Cell<u32>,
+    // Current adaptive extra read-ahead, added on top of
+    // `raftlog_fetch_read_ahead_size`, capped by `budget`.
+    window: Cell<u64>,
+    // Upper bound on `window`. `0` (the default) disables adaptive growth
+    // entirely, matching how `0` disables the static
+    // `raftlog_fetch_read_ahead_size`.
+    budget: u64,
+    // `(low, caller_high, fetch_high)` of the most recently scheduled
+    // read-ahead surplus that hasn't yet been resolved as either a hit
+    // (`claim_hit`) or wasted (`charge_unused`).
+    pending: Cell<Option<(u64, u64, u64)>>,
+}
+
+impl ReadAheadState {
+    fn set_budget(&mut self, budget: u64) {
+        self.budget = budget;
+    }
+
+    /// Called once per genuinely-new async fetch, just before it's
+    /// scheduled, with that fetch's `low`. Updates the sequential-run
+    /// detector and returns the adaptive extra read-ahead to add on top of
+    /// the static read-ahead size for this fetch.
+    fn observe(&self, low: u64) -> u64 {
+        if self.budget == 0 {
+            return 0;
+        }
+        let prev = self.last_low.replace(Some(low));
+        let run = match prev {
+            Some(p) if low >= p => self.run.get() + 1,
+            _ => {
+                // A backward jump (or the very first miss) isn't part of a
+                // sequential scan; drop any window grown by a prior run.
+                self.window.set(0);
+                0
+            }
+        };
+        self.run.set(run);
+        if run >= SEQUENTIAL_RUN_THRESHOLD {
+            let grown = cmp::max(self.window.get() * 2, MIN_ADAPTIVE_READ_AHEAD);
+            self.window.set(cmp::min(grown, self.budget));
+        }
+        self.window.get()
+    }
+
+    /// Records that `[caller_high, fetch_high)` was just scheduled as
+    /// read-ahead surplus beyond `low`'s own requested range.
+    fn record_surplus(&self, low: u64, caller_high: u64, fetch_high: u64) {
+        if fetch_high > caller_high {
+            self.pending.set(Some((low, caller_high, fetch_high)));
+        }
+    }
+
+    /// Called from the cache-hit path of a later `entries()` call with its
+    /// `low`. If that `low` falls inside the pending surplus range, the
+    /// read-ahead paid off: clears the pending entry so it can't later also
+    /// be charged as wasted, and reports the win to the caller.
+    fn claim_hit(&self, low: u64) -> bool {
+        match self.pending.get() {
+            Some((_, caller_high, fetch_high)) if low >= caller_high && low < fetch_high => {
+                self.pending.set(None);
+                true
+            }
+            _ => false,
+        }
     }
+
+    /// Called when a landed async-fetch result for `low` is dropped without
+    /// ever being consumed (the `None` path of
+    /// [`EntryStorage::update_async_fetch_res`]). If `low` is the request
+    /// the current pending surplus was attached to, the extra read-ahead
+    /// didn't pay for itself: decay the window and report the surplus range
+    /// so the caller can account its wasted bytes.
+    fn charge_unused(&self, low: u64) -> Option<(u64, u64)> {
+        match self.pending.get() {
+            Some((l, caller_high, fetch_high)) if l == low => {
+                self.pending.set(None);
+                self.decay();
+                Some((caller_high, fetch_high))
+            }
+            _ => None,
+        }
+    }
+
+    /// Halves the window and resets the run counter. Used both when a
+    /// prefetch goes unused and when `EntryStorage::evict_entry_cache`
+    /// signals memory pressure, so the window backs off gracefully instead
+    /// of snapping straight to 0 on a single bad signal.
+    fn decay(&self) {
+        self.window.set(self.window.get() / 2);
+        self.run.set(0);
+    }
+}
+
+/// Why a range of cached entries was dropped, for the
+/// `RAFT_ENTRIES_EVICT.compaction_count`/`memory_pressure_count` counters
+/// (see [`EntryStorage::record_cache_eviction`]).
+#[derive(Clone, Copy)]
+enum CacheEvictReason {
+    /// Raft log GC advancing past compacted/applied entries -- the normal,
+    /// expected path ([`EntryStorage::compact_entry_cache`]).
+    Compaction,
+    /// Forced out ahead of GC by process-wide memory pressure
+    /// ([`EntryStorage::evict_entry_cache`], and indirectly the
+    /// [`EntryCacheManager`] budget's evict callback via
+    /// [`EntryStorage::evict_entry_cache_to`]).
+    MemoryPressure,
+}
+
+/// One region's contribution to the process-wide persisted-entry budget:
+/// the byte size of its `EntryCache`'s persisted prefix, and when it was
+/// last touched by a cache hit.
+#[derive(Clone, Copy, Debug)]
+struct CacheSegment {
+    bytes: u64,
+    recency: u64,
+    // `min(applied_index, persisted_index)` (exclusive end, i.e. already a
+    // valid `compact_to` argument) for this peer, or `None` if nothing in
+    // its registered segment has been applied yet -- `EntryCache` only ever
+    // registers its *persisted* prefix in the first place (see this type's
+    // doc comment above), so the remaining gap that can make a segment
+    // wholly unevictable is the apply side, not the raft-log side.
+    safe_compact_to: Option<u64>,
+}
+
+/// Store-wide soft/hard watermarks for the aggregate persisted-entry budget
+/// tracked by [`EntryCacheManager`]. Crossing `soft_limit_bytes` is handled
+/// by the usual background LRU walk ([`EntryCacheManager::evict_if_over_budget`]);
+/// `hard_limit_bytes` is a second, higher ceiling that
+/// [`EntryStorage::append`] checks synchronously on every append
+/// ([`EntryCacheManager::evict_if_over_hard_limit`]), so a burst of writes
+/// can't outrun the background pass and OOM the process. Must have
+/// `hard_limit_bytes >= soft_limit_bytes`; the gap between them needs to be
+/// wide enough to absorb peers that are temporarily stuck (applied_index
+/// hasn't caught up, so their segment can't be evicted at all) without the
+/// two watermarks thrashing against each other.
+#[derive(Clone, Copy, Debug)]
+pub struct RaftEntryCacheBudget {
+    pub soft_limit_bytes: u64,
+    pub hard_limit_bytes: u64,
+}
+
+/// Process-wide LRU budget over every peer's `EntryCache`. Each cache
+/// registers (via [`EntryStorage::set_cache_manager`]) only its persisted
+/// prefix -- the part `EntryCache` itself already guarantees is safe to
+/// truncate, since raft-rs may still re-read unpersisted entries for an
+/// in-flight MsgAppend -- and the manager picks the least-recently-touched
+/// *evictable* registration to evict once the aggregate exceeds a
+/// watermark (see [`RaftEntryCacheBudget`]).
+///
+/// An `EntryCache` only knows how to drop its own prefix, so the manager
+/// can't evict a victim peer's entries directly; eviction instead invokes
+/// whatever hook [`Self::set_evict_callback`] was given with
+/// `(region_id, compact_to)`. Wiring that to the real raft router, so the
+/// target peer's `EntryStorage::evict_entry_cache_to` actually runs, is left
+/// to the caller, since the router lives outside this crate's entry-storage
+/// layer. Because that callback only fires asynchronously across the
+/// router, `used_bytes` is trued up optimistically: a victim's registered
+/// bytes are subtracted the moment it's picked, and corrected again once
+/// the peer's own `compact_entry_cache` re-registers a (usually much
+/// smaller) segment.
+pub struct EntryCacheManager {
+    soft_limit_bytes: AtomicU64,
+    hard_limit_bytes: AtomicU64,
+    used_bytes: AtomicU64,
+    next_recency: AtomicU64,
+    segments: Mutex<HashMap<u64, CacheSegment>>,
+    evict_callback: Mutex<Option<Arc<dyn Fn(u64, u64) + Send + Sync>>>,
+}
+
+impl EntryCacheManager {
+    pub fn new(budget: RaftEntryCacheBudget) -> Arc<Self> {
+        Arc::new(EntryCacheManager {
+            soft_limit_bytes: AtomicU64::new(budget.soft_limit_bytes),
+            hard_limit_bytes: AtomicU64::new(budget.hard_limit_bytes),
+            used_bytes: AtomicU64::new(0),
+            next_recency: AtomicU64::new(0),
+            segments: Mutex::new(HashMap::default()),
+            evict_callback: Mutex::new(None),
+        })
+    }
+
+    pub fn set_budget(&self, budget: RaftEntryCacheBudget) {
+        self.soft_limit_bytes
+            .store(budget.soft_limit_bytes, Ordering::Relaxed);
+        self.hard_limit_bytes
+            .store(budget.hard_limit_bytes, Ordering::Relaxed);
+    }
+
+    /// Registers the hook eviction calls with `(region_id, compact_to)`
+    /// when it picks a victim to evict.
+    pub fn set_evict_callback<F>(&self, f: F)
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        *self.evict_callback.lock().unwrap() = Some(Arc::new(f));
+    }
+
+    fn adjust_used_bytes(&self, delta: i64) {
+        if delta >= 0 {
+            self.used_bytes.fetch_add(delta as u64, Ordering::Relaxed);
+        } else {
+            self.used_bytes
+                .fetch_sub((-delta) as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// (Re-)registers `region_id`'s persisted segment, overwriting any
+    /// previous registration rather than accumulating -- only the current
+    /// persisted prefix is ever evictable, so the old one is stale.
+    fn register(&self, region_id: u64, bytes: u64, safe_compact_to: Option<u64>) {
+        let mut segments = self.segments.lock().unwrap();
+        let recency = self.next_recency.fetch_add(1, Ordering::Relaxed);
+        let prev_bytes = segments
+            .insert(
+                region_id,
+                CacheSegment {
+                    bytes,
+                    recency,
+                    safe_compact_to,
+                },
+            )
+            .map_or(0, |s| s.bytes);
+        self.adjust_used_bytes(bytes as i64 - prev_bytes as i64);
+    }
+
+    /// Drops `region_id`'s registration entirely, e.g. once nothing in its
+    /// cache is persisted anymore.
+    fn unregister(&self, region_id: u64) {
+        let mut segments = self.segments.lock().unwrap();
+        if let Some(s) = segments.remove(&region_id) {
+            self.adjust_used_bytes(-(s.bytes as i64));
+        }
+    }
+
+    /// Bumps `region_id`'s recency on a cache hit, making it less likely to
+    /// be picked as the next eviction victim.
+    fn touch(&self, region_id: u64) {
+        let mut segments = self.segments.lock().unwrap();
+        if let Some(s) = segments.get_mut(&region_id) {
+            s.recency = self.next_recency.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Background path: walks victims in approximate-LRU order until the
+    /// aggregate is back at or under `soft_limit_bytes`.
+    pub fn evict_if_over_budget(&self) {
+        self.evict_to(self.soft_limit_bytes.load(Ordering::Relaxed));
+    }
+
+    /// Synchronous path: same walk, but down to the looser
+    /// `hard_limit_bytes`. Meant to be called from the append path itself
+    /// ([`EntryStorage::append`]) so a write burst can't push the aggregate
+    /// past the hard ceiling before the background pass above gets a
+    /// chance to run.
+    pub fn evict_if_over_hard_limit(&self) {
+        self.evict_to(self.hard_limit_bytes.load(Ordering::Relaxed));
+    }
+
+    /// Picks the least-recently-touched *evictable* segment (one with
+    /// `safe_compact_to.is_some()`) and invokes the evict callback,
+    /// repeating until the aggregate is at or under `limit` or no evictable
+    /// segment remains. A peer stuck with `safe_compact_to: None` (applied
+    /// index hasn't caught up to anything persisted yet) is skipped rather
+    /// than blocking the whole walk -- see the watermark gap called out on
+    /// [`RaftEntryCacheBudget`].
+    fn evict_to(&self, limit: u64) {
+        let callback = match self.evict_callback.lock().unwrap().clone() {
+            Some(c) => c,
+            None => return,
+        };
+        loop {
+            if self.used_bytes.load(Ordering::Relaxed) <= limit {
+                return;
+            }
+            let victim = {
+                let mut segments = self.segments.lock().unwrap();
+                let region_id = segments
+                    .iter()
+                    .filter(|(_, s)| s.safe_compact_to.is_some())
+                    .min_by_key(|(_, s)| s.recency)
+                    .map(|(region_id, _)| *region_id);
+                region_id.map(|region_id| {
+                    // Optimistically assume the callback will fully clear
+                    // this segment; the real byte count is trued up once
+                    // the victim peer's own `compact_entry_cache` runs and
+                    // re-registers via `sync_cache_manager`.
+                    let seg = segments.remove(&region_id).unwrap();
+                    self.adjust_used_bytes(-(seg.bytes as i64));
+                    (region_id, seg.safe_compact_to.unwrap())
+                })
+            };
+            match victim {
+                Some((region_id, compact_to)) => callback(region_id, compact_to),
+                None => return,
+            }
+        }
+    }
+}
+
+/// Configures periodic, policy-driven shrinking of a single peer's entry
+/// cache, on top of the purely reactive shrinking `compact_entry_cache`
+/// already does when the raft log itself gets compacted. `gc_count_limit`
+/// keeps a bounded tail of already-applied entries around even for a peer
+/// that's fully caught up (so a lagging follower's occasional term/entry
+/// probe doesn't immediately miss the cache), while `gc_size_limit` is the
+/// more aggressive escalation: if the persisted-and-therefore-evictable
+/// segment is still over this many bytes after the count-based floor, the
+/// whole applied+persisted prefix is dropped instead. Both are evaluated
+/// together by [`EntryStorage::maybe_gc_entry_cache`], throttled to at most
+/// once per `check_interval`.
+#[derive(Clone, Copy, Debug)]
+pub struct CacheGcPolicy {
+    pub gc_count_limit: u64,
+    pub gc_size_limit: u64,
+    pub check_interval: Duration,
 }
 
 /// A subset of `PeerStorage` that focus on accessing log entries.
@@ -426,7 +1487,25 @@ pub struct EntryStorage<ER> {
     applied_term: u64,
     raftlog_fetch_scheduler: Scheduler<RaftlogFetchTask>,
     raftlog_fetch_stats: AsyncFetchStats,
-    async_fetch_results: RefCell<HashMap<u64, RaftlogFetchState>>,
+    raftlog_fetch_read_ahead_size: u64,
+    // Keyed by each fetch's starting index and kept sorted so a new request
+    // can be checked against the nearest in-flight/landed range below it
+    // with a single `range(..=low).next_back()` lookup -- a BTreeMap used
+    // as a simple interval index, good enough for the low fan-out of
+    // concurrently overlapping fetches in practice (typically just a probe
+    // and a stream for the same region) without pulling in a dedicated
+    // interval-tree crate.
+    async_fetch_results: RefCell<BTreeMap<u64, RaftlogFetchState>>,
+    cache_manager: Option<Arc<EntryCacheManager>>,
+    term_cache: TermCache,
+    read_ahead: ReadAheadState,
+    // Highest index below which this peer's cache has ever dropped an
+    // entry (see `record_cache_eviction`). Lets `entries()`/`term()` tell
+    // an eviction-triggered disk read -- one for an index that used to be
+    // cached -- apart from an ordinary cold read for `RAFT_ENTRIES_EVICT_DISK_READS`.
+    evicted_watermark: Cell<u64>,
+    gc_policy: Option<CacheGcPolicy>,
+    last_gc_check: Cell<Option<Instant>>,
 }
 
 impl<ER: RaftEngine> EntryStorage<ER> {
@@ -451,7 +1530,107 @@ impl<ER: RaftEngine> EntryStorage<ER> {
             applied_term,
             raftlog_fetch_scheduler,
             raftlog_fetch_stats: AsyncFetchStats::default(),
-            async_fetch_results: RefCell::new(HashMap::default()),
+            raftlog_fetch_read_ahead_size: 0,
+            async_fetch_results: RefCell::new(BTreeMap::new()),
+            cache_manager: None,
+            term_cache: TermCache::default(),
+            read_ahead: ReadAheadState::default(),
+            evicted_watermark: Cell::new(0),
+            gc_policy: None,
+            last_gc_check: Cell::new(None),
+        }
+    }
+
+    /// Sets how far past a requested `high` an async fetch should read
+    /// ahead (see [`Self::async_fetch`]), so a lagging follower being
+    /// caught up sequentially warms the next range instead of missing the
+    /// cache again immediately. `0` (the default) disables read-ahead.
+    pub fn set_raftlog_fetch_read_ahead_size(&mut self, size: u64) {
+        self.raftlog_fetch_read_ahead_size = size;
+    }
+
+    /// Opts into growing the read-ahead window beyond
+    /// [`Self::set_raftlog_fetch_read_ahead_size`] while `async_fetch`
+    /// detects a sequential catch-up scan, up to `budget` extra entries
+    /// (see [`ReadAheadState`]). `0` (the default) disables the adaptive
+    /// window; the static size above still applies on its own.
+    pub fn set_adaptive_read_ahead_budget(&mut self, budget: u64) {
+        self.read_ahead.set_budget(budget);
+    }
+
+    /// Opts into periodic cache shrinking under `policy` (see
+    /// [`CacheGcPolicy`] and [`Self::maybe_gc_entry_cache`]), in addition to
+    /// the reactive shrinking `compact_entry_cache` already does on log
+    /// compaction. Not set by default, i.e. no periodic tick runs.
+    pub fn set_gc_policy(&mut self, policy: CacheGcPolicy) {
+        self.gc_policy = Some(policy);
+    }
+
+    /// Opts into LZ4 compression of cached entries once they're both
+    /// persisted and at least `threshold` bytes of combined `data`/`context`
+    /// (see [`EntryCache::compress_if_eligible`]). `None` (the default)
+    /// disables compression entirely.
+    pub fn set_compress_threshold(&mut self, threshold: Option<u64>) {
+        self.cache.compress_threshold = threshold;
+    }
+
+    /// Opts into content-defined chunk dedup of persisted cached entries'
+    /// `data` once it's at least `threshold` bytes (see
+    /// [`EntryCache::chunk_if_eligible`]). Takes priority over
+    /// [`Self::set_compress_threshold`] per entry when both are set. `None`
+    /// (the default) disables chunking entirely.
+    pub fn set_chunk_threshold(&mut self, threshold: Option<u64>) {
+        self.cache.chunk_threshold = threshold;
+    }
+
+    /// Opts this peer's `EntryCache` into the process-wide
+    /// [`EntryCacheManager`] budget: its current persisted prefix is
+    /// registered immediately, and kept in sync on every subsequent
+    /// `append`/`compact_entry_cache`/`update_cache_persisted` call.
+    pub fn set_cache_manager(&mut self, manager: Arc<EntryCacheManager>) {
+        self.cache_manager = Some(manager);
+        self.sync_cache_manager();
+    }
+
+    fn sync_cache_manager(&self) {
+        let manager = match self.cache_manager.as_ref() {
+            Some(m) => m,
+            None => return,
+        };
+        match self.cache.persisted_segment() {
+            Some((range, bytes)) => {
+                // Never let the manager evict past what's actually been
+                // applied, even though `range.end` already guarantees it
+                // won't evict past what's persisted.
+                let compact_to = cmp::min(range.end, self.applied_index() + 1);
+                let safe_compact_to = if compact_to > range.start {
+                    Some(compact_to)
+                } else {
+                    None
+                };
+                manager.register(self.region_id, bytes, safe_compact_to);
+            }
+            None => manager.unregister(self.region_id),
+        }
+    }
+
+    /// Splices the entries of a landed async-fetch result into the front of
+    /// `self.cache`, if they're contiguous with it. This is the only place
+    /// that sees the fetch result before [`Self::async_fetch`] truncates it
+    /// down to the caller's originally-requested `high`, so a read-ahead
+    /// tail (see [`Self::set_raftlog_fetch_read_ahead_size`]) that reached
+    /// past that `high` would otherwise just be thrown away.
+    fn warm_cache_with_fetch_res(&mut self, res: &RaftlogFetchResult) {
+        let ents = match &res.ents {
+            Ok(ents) if !ents.is_empty() => ents,
+            _ => return,
+        };
+        let warmed = self.cache.prepend(ents);
+        if warmed > 0 {
+            self.raftlog_fetch_stats
+                .warmed_entries
+                .update(|m| m + warmed as u64);
+            self.sync_cache_manager();
         }
     }
 
@@ -473,22 +1652,40 @@ impl<ER: RaftEngine> EntryStorage<ER> {
         Ok(())
     }
 
-    pub fn clean_async_fetch_res(&mut self, low: u64) {
-        self.async_fetch_results.borrow_mut().remove(&low);
+    /// Drops every tracked fetch (in flight or landed) whose range overlaps
+    /// `[low, high)`, rather than a single entry keyed by `low` -- a
+    /// coalesced fetch may be tracked under a key below `low` that this
+    /// caller never scheduled itself.
+    pub fn clean_async_fetch_res(&mut self, low: u64, high: u64) {
+        self.async_fetch_results.borrow_mut().retain(|&key, state| {
+            let (state_low, state_high) = match state {
+                RaftlogFetchState::Fetching {
+                    high: fetching_high,
+                } => (key, *fetching_high),
+                RaftlogFetchState::Fetched(res) => {
+                    let len = res.ents.as_ref().map_or(0, |ents| ents.len() as u64);
+                    (res.low, res.low + len)
+                }
+            };
+            !(state_low < high && low < state_high)
+        });
     }
 
     // Update the async fetch result.
     // None indicates cleanning the fetched result.
     pub fn update_async_fetch_res(&mut self, low: u64, res: Option<Box<RaftlogFetchResult>>) {
         // If it's in fetching, don't clean the async fetch result.
-        if self.async_fetch_results.borrow().get(&low) == Some(&RaftlogFetchState::Fetching)
-            && res.is_none()
+        if matches!(
+            self.async_fetch_results.borrow().get(&low),
+            Some(RaftlogFetchState::Fetching { .. })
+        ) && res.is_none()
         {
             return;
         }
 
         match res {
             Some(res) => {
+                self.warm_cache_with_fetch_res(&res);
                 if let Some(RaftlogFetchState::Fetched(prev)) = self
                     .async_fetch_results
                     .borrow_mut()
@@ -505,8 +1702,29 @@ impl<ER: RaftEngine> EntryStorage<ER> {
             }
             None => {
                 let prev = self.async_fetch_results.borrow_mut().remove(&low);
-                if prev.is_some() {
+                if let Some(state) = prev {
                     self.raftlog_fetch_stats.fetch_unused.update(|m| m + 1);
+                    if let Some((caller_high, fetch_high)) = self.read_ahead.charge_unused(low) {
+                        let wasted = match &state {
+                            RaftlogFetchState::Fetched(res) => {
+                                res.ents.as_ref().map_or(0, |ents| {
+                                    ents.iter()
+                                        .filter(|e| {
+                                            e.get_index() >= caller_high
+                                                && e.get_index() < fetch_high
+                                        })
+                                        .map(|e| e.compute_size() as u64)
+                                        .sum()
+                                })
+                            }
+                            RaftlogFetchState::Fetching { .. } => 0,
+                        };
+                        if wasted > 0 {
+                            self.raftlog_fetch_stats
+                                .prefetch_wasted_bytes
+                                .update(|m| m + wasted);
+                        }
+                    }
                 }
             }
         }
@@ -521,90 +1739,118 @@ impl<ER: RaftEngine> EntryStorage<ER> {
         context: GetEntriesContext,
         buf: &mut Vec<Entry>,
     ) -> raft::Result<usize> {
-        if let Some(RaftlogFetchState::Fetching) = self.async_fetch_results.borrow().get(&low) {
-            // already an async fetch in flight
-            return Err(raft::Error::Store(
-                raft::StorageError::LogTemporarilyUnavailable,
-            ));
+        // Look for the nearest tracked fetch starting at or below `low`: with
+        // keys kept sorted by their starting index, that single candidate is
+        // enough to tell whether some earlier, wider request already covers
+        // this one (two requests overlapping further apart than that would
+        // each have their own non-overlapping left edge below `low` instead).
+        let covering_low = self
+            .async_fetch_results
+            .borrow()
+            .range(..=low)
+            .next_back()
+            .map(|(&key, _)| key);
+
+        if let Some(key) = covering_low {
+            if let Some(RaftlogFetchState::Fetching {
+                high: fetching_high,
+            }) = self.async_fetch_results.borrow().get(&key)
+            {
+                if *fetching_high >= high {
+                    // an in-flight fetch already covers [low, high)
+                    return Err(raft::Error::Store(
+                        raft::StorageError::LogTemporarilyUnavailable,
+                    ));
+                }
+            }
         }
 
         let tried_cnt = if let Some(RaftlogFetchState::Fetched(res)) =
-            self.async_fetch_results.borrow_mut().remove(&low)
+            covering_low.and_then(|key| self.async_fetch_results.borrow_mut().remove(&key))
         {
-            assert_eq!(res.low, low);
             let mut ents = res.ents?;
             let first = ents.first().map(|e| e.index).unwrap();
-            assert_eq!(first, res.low);
+            assert!(first <= low);
             let last = ents.last().map(|e| e.index).unwrap();
-
-            if last + 1 >= high {
-                // async fetch res covers [low, high)
-                ents.truncate((high - first) as usize);
-                assert_eq!(ents.last().map(|e| e.index).unwrap(), high - 1);
-                if max_size < res.max_size {
-                    limit_size(&mut ents, Some(max_size));
-                }
-                let count = ents.len();
-                buf.append(&mut ents);
-                fail_point!("on_async_fetch_return");
-                return Ok(count);
-            } else if res.hit_size_limit && max_size <= res.max_size {
-                // async fetch res doesn't cover [low, high) due to hit size limit
-                if max_size < res.max_size {
-                    limit_size(&mut ents, Some(max_size));
-                };
-                let count = ents.len();
-                buf.append(&mut ents);
-                return Ok(count);
-            } else if last + RAFT_LOG_MULTI_GET_CNT > high - 1
-                && res.tried_cnt + 1 == MAX_ASYNC_FETCH_TRY_CNT
-            {
-                let mut fetched_size = ents.iter().fold(0, |acc, e| acc + e.compute_size() as u64);
-                if max_size <= fetched_size {
-                    limit_size(&mut ents, Some(max_size));
+            if last < low {
+                // the landed fetch doesn't actually reach this request's
+                // range; put it aside as invalid below rather than treating
+                // it as a hit.
+                self.raftlog_fetch_stats.fetch_invalid.update(|m| m + 1);
+                res.tried_cnt + 1
+            } else {
+                ents.drain(..(low - first) as usize);
+
+                if last + 1 >= high {
+                    // async fetch res covers [low, high)
+                    ents.truncate((high - low) as usize);
+                    assert_eq!(ents.last().map(|e| e.index).unwrap(), high - 1);
+                    if max_size < res.max_size {
+                        limit_size(&mut ents, Some(max_size));
+                    }
                     let count = ents.len();
                     buf.append(&mut ents);
+                    fail_point!("on_async_fetch_return");
                     return Ok(count);
-                }
+                } else if res.hit_size_limit && max_size <= res.max_size {
+                    // async fetch res doesn't cover [low, high) due to hit size limit
+                    if max_size < res.max_size {
+                        limit_size(&mut ents, Some(max_size));
+                    };
+                    let count = ents.len();
+                    buf.append(&mut ents);
+                    return Ok(count);
+                } else if last + RAFT_LOG_MULTI_GET_CNT > high - 1
+                    && res.tried_cnt + 1 == MAX_ASYNC_FETCH_TRY_CNT
+                {
+                    let mut fetched_size =
+                        ents.iter().fold(0, |acc, e| acc + e.compute_size() as u64);
+                    if max_size <= fetched_size {
+                        limit_size(&mut ents, Some(max_size));
+                        let count = ents.len();
+                        buf.append(&mut ents);
+                        return Ok(count);
+                    }
 
-                // the count of left entries isn't too large, fetch the remaining entries
-                // synchronously one by one
-                for idx in last + 1..high {
-                    let ent = self.raft_engine.get_entry(region_id, idx)?;
-                    match ent {
-                        None => {
-                            return Err(raft::Error::Store(raft::StorageError::Unavailable));
-                        }
-                        Some(ent) => {
-                            let size = ent.compute_size() as u64;
-                            if fetched_size + size > max_size {
-                                break;
-                            } else {
-                                fetched_size += size;
-                                ents.push(ent);
+                    // the count of left entries isn't too large, fetch the remaining entries
+                    // synchronously one by one
+                    for idx in last + 1..high {
+                        let ent = self.raft_engine.get_entry(region_id, idx)?;
+                        match ent {
+                            None => {
+                                return Err(raft::Error::Store(raft::StorageError::Unavailable));
+                            }
+                            Some(ent) => {
+                                let size = ent.compute_size() as u64;
+                                if fetched_size + size > max_size {
+                                    break;
+                                } else {
+                                    fetched_size += size;
+                                    ents.push(ent);
+                                }
                             }
                         }
                     }
+                    let count = ents.len();
+                    buf.append(&mut ents);
+                    return Ok(count);
                 }
-                let count = ents.len();
-                buf.append(&mut ents);
-                return Ok(count);
+                info!(
+                    "async fetch invalid";
+                    "region_id" => self.region_id,
+                    "peer_id" => self.peer_id,
+                    "first" => first,
+                    "last" => last,
+                    "low" => low,
+                    "high" => high,
+                    "max_size" => max_size,
+                    "res_max_size" => res.max_size,
+                );
+                // low index or max size is changed, the result is not fit for the current
+                // range, so refetch again.
+                self.raftlog_fetch_stats.fetch_invalid.update(|m| m + 1);
+                res.tried_cnt + 1
             }
-            info!(
-                "async fetch invalid";
-                "region_id" => self.region_id,
-                "peer_id" => self.peer_id,
-                "first" => first,
-                "last" => last,
-                "low" => low,
-                "high" => high,
-                "max_size" => max_size,
-                "res_max_size" => res.max_size,
-            );
-            // low index or max size is changed, the result is not fit for the current
-            // range, so refetch again.
-            self.raftlog_fetch_stats.fetch_invalid.update(|m| m + 1);
-            res.tried_cnt + 1
         } else {
             1
         };
@@ -628,15 +1874,32 @@ impl<ER: RaftEngine> EntryStorage<ER> {
         }
 
         self.raftlog_fetch_stats.async_fetch.update(|m| m + 1);
+        // Read past the caller's `high` when configured to, so the result
+        // also warms the cache for the next sequential read (see
+        // `Self::warm_cache_with_fetch_res`). Never read past `last_index`,
+        // and the caller's own interpretation of the result below still
+        // only looks at `[low, high)`. This also widens the range other
+        // concurrently overlapping requests can be coalesced against. On
+        // top of the static size, `ReadAheadState::observe` adds an
+        // adaptive extra once it's seen a run of sequential misses.
+        let read_ahead = self
+            .raftlog_fetch_read_ahead_size
+            .saturating_add(self.read_ahead.observe(low));
+        let fetch_high = if read_ahead > 0 {
+            cmp::min(high.saturating_add(read_ahead), self.last_index() + 1)
+        } else {
+            high
+        };
+        self.read_ahead.record_surplus(low, high, fetch_high);
         self.async_fetch_results
             .borrow_mut()
-            .insert(low, RaftlogFetchState::Fetching);
+            .insert(low, RaftlogFetchState::Fetching { high: fetch_high });
         self.raftlog_fetch_scheduler
             .schedule(RaftlogFetchTask::PeerStorage {
                 region_id,
                 context,
                 low,
-                high,
+                high: fetch_high,
                 max_size: (max_size as usize),
                 tried_cnt,
                 term: self.hard_state().get_term(),
@@ -663,6 +1926,7 @@ impl<ER: RaftEngine> EntryStorage<ER> {
         let cache_low = self.cache.first_index().unwrap_or(u64::MAX);
         if high <= cache_low {
             self.cache.miss.update(|m| m + 1);
+            self.record_disk_read_if_evicted(low);
             return if context.can_async() {
                 self.async_fetch(self.region_id, low, high, max_size, context, &mut ents)?;
                 Ok(ents)
@@ -680,6 +1944,7 @@ impl<ER: RaftEngine> EntryStorage<ER> {
         }
         let begin_idx = if low < cache_low {
             self.cache.miss.update(|m| m + 1);
+            self.record_disk_read_if_evicted(low);
             let fetched_count = if context.can_async() {
                 self.async_fetch(self.region_id, low, cache_low, max_size, context, &mut ents)?
             } else {
@@ -701,6 +1966,12 @@ impl<ER: RaftEngine> EntryStorage<ER> {
             low
         };
         self.cache.hit.update(|h| h + 1);
+        if self.read_ahead.claim_hit(low) {
+            self.raftlog_fetch_stats.prefetch_hit.update(|m| m + 1);
+        }
+        if let Some(manager) = self.cache_manager.as_ref() {
+            manager.touch(self.region_id);
+        }
         let fetched_size = ents.iter().fold(0, |acc, e| acc + e.compute_size());
         self.cache
             .fetch_entries_to(begin_idx, high, fetched_size as u64, max_size, &mut ents);
@@ -716,14 +1987,25 @@ impl<ER: RaftEngine> EntryStorage<ER> {
             return Ok(self.last_term);
         }
         if let Some(e) = self.cache.entry(idx) {
-            Ok(e.get_term())
-        } else {
-            Ok(self
-                .raft_engine
-                .get_entry(self.region_id, idx)
-                .unwrap()
-                .unwrap()
-                .get_term())
+            self.cache.hit.update(|h| h + 1);
+            if let Some(manager) = self.cache_manager.as_ref() {
+                manager.touch(self.region_id);
+            }
+            return Ok(e.get_term());
+        }
+        if let Some(term) = self.term_cache.get(idx) {
+            RAFT_ENTRY_FETCHES.term_cache_hit.inc_by(1);
+            return Ok(term);
+        }
+        self.cache.miss.update(|m| m + 1);
+        self.record_disk_read_if_evicted(idx);
+        match self.raft_engine.get_entry(self.region_id, idx) {
+            Ok(Some(e)) => Ok(e.get_term()),
+            Ok(None) => Err(raft::Error::Store(StorageError::Unavailable)),
+            Err(e) => Err(storage_error(format!(
+                "failed to read entry {} from raft engine: {:?}",
+                idx, e
+            ))),
         }
     }
 
@@ -833,6 +2115,14 @@ impl<ER: RaftEngine> EntryStorage<ER> {
         };
 
         self.cache.append(self.region_id, self.peer_id, &entries);
+        self.term_cache.append(&entries);
+        self.sync_cache_manager();
+        if let Some(manager) = self.cache_manager.as_ref() {
+            // Synchronous hard-watermark check: a single peer's own append
+            // can be what tips the aggregate over, so it must not have to
+            // wait for some other tick to run the background eviction walk.
+            manager.evict_if_over_hard_limit();
+        }
 
         task.entries = entries;
         // Delete any previously appended log entries which never committed.
@@ -843,7 +2133,87 @@ impl<ER: RaftEngine> EntryStorage<ER> {
     }
 
     pub fn compact_entry_cache(&mut self, idx: u64) {
-        self.cache.compact_to(idx);
+        self.compact_entry_cache_as(idx, CacheEvictReason::Compaction);
+    }
+
+    /// Same mechanics as [`Self::compact_entry_cache`], but tagged as a
+    /// memory-pressure eviction for the per-reason counters in
+    /// [`Self::record_cache_eviction`]. This is what the
+    /// [`EntryCacheManager`] budget's evict callback should call: from this
+    /// side of that callback a budget-triggered drop is otherwise
+    /// indistinguishable from an ordinary GC compaction.
+    pub fn evict_entry_cache_to(&mut self, idx: u64) {
+        self.compact_entry_cache_as(idx, CacheEvictReason::MemoryPressure);
+    }
+
+    fn compact_entry_cache_as(&mut self, idx: u64, reason: CacheEvictReason) {
+        let (bytes, count) = self.cache.compact_to(idx);
+        if let CacheEvictReason::Compaction = reason {
+            self.term_cache.compact_to(idx);
+        }
+        self.sync_cache_manager();
+        self.record_cache_eviction(reason, bytes, count);
+    }
+
+    /// Driven by an external periodic tick (e.g. a `PeerTick` variant on a
+    /// raftstore tick interval); a no-op if [`Self::set_gc_policy`] was
+    /// never called, or if `check_interval` hasn't elapsed since the last
+    /// call that actually ran the check. Unlike the log-compaction-driven
+    /// [`Self::compact_entry_cache`], this computes its own target rather
+    /// than taking one from the caller, so an idle peer's cache still
+    /// shrinks even though nothing ever compacts its log: the floor is
+    /// `applied_idx - gc_count_limit`, escalated to the whole
+    /// applied+persisted prefix if the persisted segment is still over
+    /// `gc_size_limit` afterwards. Returns whether it actually compacted
+    /// anything.
+    pub fn maybe_gc_entry_cache(&mut self) -> bool {
+        let policy = match self.gc_policy {
+            Some(policy) => policy,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        if let Some(last) = self.last_gc_check.get() {
+            if now.duration_since(last) < policy.check_interval {
+                return false;
+            }
+        }
+        self.last_gc_check.set(Some(now));
+
+        let compacted_idx = self.truncated_index();
+        let applied_idx = self.applied_index();
+        let mut target = cmp::max(
+            compacted_idx,
+            applied_idx.saturating_sub(policy.gc_count_limit),
+        );
+
+        if let Some((range, _)) = self.cache.persisted_segment() {
+            // Bytes of the persisted segment that the count-based floor
+            // leaves in place.
+            let remaining: u64 = self
+                .cache
+                .cache
+                .iter()
+                .filter(|e| e.get_index() >= target && e.get_index() < range.end)
+                .map(|e| (bytes_capacity(&e.data) + bytes_capacity(&e.context)) as u64)
+                .sum();
+            if remaining > policy.gc_size_limit {
+                // The count-based floor isn't shrinking fast enough to stay
+                // under budget; drop the whole applied+persisted prefix
+                // instead. `compact_to` clamps to what's actually persisted
+                // regardless, so this can never touch an entry raft-rs
+                // might still read for an in-flight MsgAppend.
+                target = cmp::max(target, applied_idx + 1);
+            }
+        }
+
+        if target <= compacted_idx {
+            // Nothing to do; avoid the silent-no-op call the request calls
+            // out (`compact_entry_cache` on a stale index).
+            return false;
+        }
+        self.compact_entry_cache(target);
+        true
     }
 
     #[inline]
@@ -851,6 +2221,41 @@ impl<ER: RaftEngine> EntryStorage<ER> {
         self.cache.is_empty()
     }
 
+    /// Counts `idx` as an eviction-triggered disk read
+    /// (`RAFT_ENTRIES_EVICT_DISK_READS`) if it falls below
+    /// `evicted_watermark`, i.e. it used to be served from the cache until
+    /// some earlier [`Self::record_cache_eviction`] dropped it. Called from
+    /// every cache-miss fallback in [`Self::entries`]/[`Self::term`].
+    fn record_disk_read_if_evicted(&self, idx: u64) {
+        if idx < self.evicted_watermark.get() {
+            RAFT_ENTRIES_EVICT_DISK_READS.inc_by(1);
+        }
+    }
+
+    /// Records the result of a cache-shrinking call to the per-reason
+    /// eviction metrics (see [`CacheEvictReason`]), and -- if anything was
+    /// actually reclaimed -- raises `evicted_watermark` so
+    /// `entries()`/`term()` can tell an eviction-triggered disk read apart
+    /// from an ordinary cold one.
+    fn record_cache_eviction(&self, reason: CacheEvictReason, bytes: u64, count: usize) {
+        RAFT_ENTRIES_EVICT_BYTES.inc_by(bytes);
+        if count == 0 {
+            return;
+        }
+        match reason {
+            CacheEvictReason::Compaction => {
+                RAFT_ENTRIES_EVICT.compaction_count.inc_by(count as u64)
+            }
+            CacheEvictReason::MemoryPressure => RAFT_ENTRIES_EVICT
+                .memory_pressure_count
+                .inc_by(count as u64),
+        }
+        let new_watermark = self.cache.first_index().unwrap_or(self.last_index() + 1);
+        if new_watermark > self.evicted_watermark.get() {
+            self.evicted_watermark.set(new_watermark);
+        }
+    }
+
     /// Evict entries from the cache.
     pub fn evict_entry_cache(&mut self, half: bool) {
         if !self.is_entry_cache_empty() {
@@ -858,8 +2263,13 @@ impl<ER: RaftEngine> EntryStorage<ER> {
             let cache_len = cache.cache.len();
             let drain_to = if half { cache_len / 2 } else { cache_len - 1 };
             let idx = cache.cache[drain_to].index;
-            let mem_size_change = cache.compact_to(idx + 1);
-            RAFT_ENTRIES_EVICT_BYTES.inc_by(mem_size_change);
+            let (bytes, count) = cache.compact_to(idx + 1);
+            self.record_cache_eviction(CacheEvictReason::MemoryPressure, bytes, count);
+            // Under memory pressure, the adaptive read-ahead window (see
+            // `ReadAheadState`) is exactly the thing that would make the
+            // pressure worse next time, so back it off here too rather
+            // than waiting for a prefetch to land unused.
+            self.read_ahead.decay();
         }
     }
 
@@ -876,6 +2286,7 @@ impl<ER: RaftEngine> EntryStorage<ER> {
 
     pub fn update_cache_persisted(&mut self, persisted: u64) {
         self.cache.update_persisted(persisted);
+        self.sync_cache_manager();
     }
 
     pub fn trace_cached_entries(&mut self, entries: CachedEntries) {
@@ -884,6 +2295,9 @@ impl<ER: RaftEngine> EntryStorage<ER> {
 
     pub fn clear(&mut self) {
         self.cache = EntryCache::default();
+        if let Some(manager) = self.cache_manager.as_ref() {
+            manager.unregister(self.region_id);
+        }
     }
 }
 
@@ -909,6 +2323,15 @@ pub mod tests {
                 trace: Default::default(),
                 hit: Cell::new(0),
                 miss: Cell::new(0),
+                compress_threshold: None,
+                compressed: HashMap::default(),
+                decompress_count: Cell::new(0),
+                compress_original_bytes: Cell::new(0),
+                compress_bytes: Cell::new(0),
+                chunk_threshold: None,
+                chunked: HashMap::default(),
+                chunk_pool: ChunkPool::default(),
+                chunk_dedup_hits: Cell::new(0),
                 size_change_cb: Some(Box::new(cb) as Box<dyn Fn(i64) + Send + 'static>),
             };
             entry_cache.flush_mem_size_change(entry_cache.total_mem_size());
@@ -1244,7 +2667,7 @@ pub mod tests {
             tests.drain(..).enumerate()
         {
             if async_res.low != lo {
-                store.clean_async_fetch_res(lo);
+                store.clean_async_fetch_res(lo, hi);
             } else {
                 store.update_async_fetch_res(lo, Some(Box::new(async_res)));
             }
@@ -1421,4 +2844,65 @@ pub mod tests {
         // invalid compaction should be ignored.
         store.compact_entry_cache(6);
     }
+
+    /// Feeds `EntryCache::compact_to` every boundary a caller could pass --
+    /// below the first cached index, exactly at the first and last indexes,
+    /// past the last index, and against an already-empty cache -- and
+    /// checks that none of them panic and that whatever survives is a
+    /// contiguous suffix with its original terms intact.
+    #[test]
+    fn test_compact_to_bounds_never_panic() {
+        let mut cache = EntryCache::default();
+        // Empty cache: any target is a no-op, not a panic.
+        assert_eq!(cache.compact_to(0), (0, 0));
+        assert_eq!(cache.compact_to(100), (0, 0));
+
+        let entries: Vec<Entry> = (10..20).map(|i| new_entry(i, 1)).collect();
+        cache.append(0, 0, &entries);
+        cache.persisted = 19;
+
+        // Below first_index: no-op.
+        assert_eq!(cache.compact_to(5), (0, 0));
+        assert_eq!(cache.cache.len(), 10);
+
+        // Exactly at first_index: still a no-op, `compact_to` only drops
+        // indexes strictly below its argument.
+        assert_eq!(cache.compact_to(10).1, 0);
+        assert_eq!(cache.cache.len(), 10);
+
+        // Mid-range boundary.
+        let (_, count) = cache.compact_to(15);
+        assert_eq!(count, 5);
+        assert_eq!(cache.first_index(), Some(15));
+        for (i, e) in cache.cache.iter().enumerate() {
+            assert_eq!(e.get_index(), 15 + i as u64);
+            assert_eq!(e.get_term(), 1);
+        }
+
+        // Past last_index: clamped internally to `persisted + 1`, draining
+        // whatever remains rather than panicking on an out-of-range offset.
+        let (_, count) = cache.compact_to(1000);
+        assert_eq!(count, 5);
+        assert!(cache.cache.is_empty());
+
+        // Compacting an already-empty cache again must not panic either.
+        assert_eq!(cache.compact_to(1000), (0, 0));
+    }
+
+    /// Same boundary sweep for `EntryCache::entry`, whose direct
+    /// `self.cache[..]` indexing goes through `index_to_offset`.
+    #[test]
+    fn test_entry_bounds_never_panic() {
+        let mut cache = EntryCache::default();
+        assert_eq!(cache.entry(5), None);
+
+        let entries: Vec<Entry> = (10..15).map(|i| new_entry(i, 2)).collect();
+        cache.append(0, 0, &entries);
+
+        assert_eq!(cache.entry(9), None);
+        assert_eq!(cache.entry(10), Some(&entries[0]));
+        assert_eq!(cache.entry(14), Some(&entries[4]));
+        assert_eq!(cache.entry(15), None);
+        assert_eq!(cache.entry(u64::MAX), None);
+    }
 }
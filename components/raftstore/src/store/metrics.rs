@@ -0,0 +1,132 @@
+// Copyright 2022 TiKV Project Authors. Licensed under Apache-2.0.
+
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, register_int_gauge, IntCounter, IntGauge};
+
+/// Per-reason counters for `EntryCache::entry`/`entries` lookups and the
+/// async-fetch/read-ahead machinery around them; see `entry_storage.rs`.
+pub struct RaftEntryFetchMetrics {
+    pub async_fetch: IntCounter,
+    pub sync_fetch: IntCounter,
+    pub fallback_fetch: IntCounter,
+    pub fetch_invalid: IntCounter,
+    pub fetch_unused: IntCounter,
+    pub hit: IntCounter,
+    pub miss: IntCounter,
+    pub decompress: IntCounter,
+    pub warmed: IntCounter,
+    pub prefetch_hit: IntCounter,
+    pub prefetch_wasted_bytes: IntCounter,
+    pub chunk_reassemble: IntCounter,
+    pub term_cache_hit: IntCounter,
+}
+
+lazy_static! {
+    pub static ref RAFT_ENTRY_FETCHES: RaftEntryFetchMetrics = RaftEntryFetchMetrics {
+        async_fetch: register_int_counter!(
+            "tikv_raftstore_entry_fetches_async_fetch",
+            "Total number of async raft log fetch tasks scheduled"
+        )
+        .unwrap(),
+        sync_fetch: register_int_counter!(
+            "tikv_raftstore_entry_fetches_sync_fetch",
+            "Total number of raft log fetches served synchronously"
+        )
+        .unwrap(),
+        fallback_fetch: register_int_counter!(
+            "tikv_raftstore_entry_fetches_fallback_fetch",
+            "Total number of async fetches that fell back to a synchronous read"
+        )
+        .unwrap(),
+        fetch_invalid: register_int_counter!(
+            "tikv_raftstore_entry_fetches_fetch_invalid",
+            "Total number of async fetch results discarded as no longer valid"
+        )
+        .unwrap(),
+        fetch_unused: register_int_counter!(
+            "tikv_raftstore_entry_fetches_fetch_unused",
+            "Total number of async fetch results that landed but were never consumed"
+        )
+        .unwrap(),
+        hit: register_int_counter!(
+            "tikv_raftstore_entry_fetches_hit",
+            "Total number of raft log entry reads served from the entry cache"
+        )
+        .unwrap(),
+        miss: register_int_counter!(
+            "tikv_raftstore_entry_fetches_miss",
+            "Total number of raft log entry reads that missed the entry cache"
+        )
+        .unwrap(),
+        decompress: register_int_counter!(
+            "tikv_raftstore_entry_fetches_decompress",
+            "Total number of entry cache reads that had to LZ4-decompress a stored entry"
+        )
+        .unwrap(),
+        warmed: register_int_counter!(
+            "tikv_raftstore_entry_fetches_warmed",
+            "Total number of entries spliced into the cache from a landed read-ahead result"
+        )
+        .unwrap(),
+        prefetch_hit: register_int_counter!(
+            "tikv_raftstore_entry_fetches_prefetch_hit",
+            "Total number of reads served from the cache thanks to adaptive read-ahead"
+        )
+        .unwrap(),
+        prefetch_wasted_bytes: register_int_counter!(
+            "tikv_raftstore_entry_fetches_prefetch_wasted_bytes",
+            "Total bytes of read-ahead surplus that landed but were never consumed"
+        )
+        .unwrap(),
+        chunk_reassemble: register_int_counter!(
+            "tikv_raftstore_entry_fetches_chunk_reassemble",
+            "Total number of entry cache reads that had to reassemble a chunked entry"
+        )
+        .unwrap(),
+        term_cache_hit: register_int_counter!(
+            "tikv_raftstore_entry_fetches_term_cache_hit",
+            "Total number of term() lookups served from the term cache"
+        )
+        .unwrap(),
+    };
+    pub static ref ENTRY_CACHE_COMPRESSION_RATIO_GAUGE: IntGauge = register_int_gauge!(
+        "tikv_raftstore_entry_cache_compression_ratio_permille",
+        "Running LZ4 compression ratio of the entry cache, in permille (compressed/original * 1000)"
+    )
+    .unwrap();
+    pub static ref RAFT_ENTRIES_CACHES_GAUGE: IntGauge = register_int_gauge!(
+        "tikv_raftstore_entry_cache_size_bytes",
+        "Memory currently held by the entry cache"
+    )
+    .unwrap();
+    pub static ref RAFT_ENTRIES_EVICT_BYTES: IntCounter = register_int_counter!(
+        "tikv_raftstore_entry_cache_evict_bytes",
+        "Total bytes evicted from the entry cache"
+    )
+    .unwrap();
+    pub static ref RAFT_ENTRIES_EVICT_DISK_READS: IntCounter = register_int_counter!(
+        "tikv_raftstore_entry_cache_evict_disk_reads",
+        "Total number of reads that fell back to disk for an index the entry cache had \
+         already evicted"
+    )
+    .unwrap();
+    pub static ref RAFT_ENTRIES_EVICT: RaftEntriesEvictMetrics = RaftEntriesEvictMetrics {
+        compaction_count: register_int_counter!(
+            "tikv_raftstore_entry_cache_evict_compaction_count",
+            "Total number of entries evicted from the entry cache by raft log GC"
+        )
+        .unwrap(),
+        memory_pressure_count: register_int_counter!(
+            "tikv_raftstore_entry_cache_evict_memory_pressure_count",
+            "Total number of entries evicted from the entry cache by memory pressure"
+        )
+        .unwrap(),
+    };
+}
+
+/// Per-reason counters for entries dropped from the entry cache; see
+/// `CacheEvictReason` in `entry_storage.rs`.
+pub struct RaftEntriesEvictMetrics {
+    pub compaction_count: IntCounter,
+    pub memory_pressure_count: IntCounter,
+}
@@ -12,12 +12,14 @@
 
 use std::{
     cmp,
+    collections::HashMap,
     convert::TryFrom,
     env, fmt,
     fs::{self, File},
     net::SocketAddr,
     path::{Path, PathBuf},
     sync::{atomic::AtomicU64, Arc, Mutex},
+    thread,
     time::{Duration, Instant},
 };
 
@@ -28,9 +30,11 @@ use engine_rocks::{
     RocksEngine,
 };
 use engine_traits::{
-    compaction_job::CompactionJobInfo, Engines, RaftEngine, ALL_CFS, CF_DEFAULT, CF_WRITE,
+    compaction_job::CompactionJobInfo, CompactExt, Engines, IterOptions, Iterable,
+    Iterator as EngineIterator, RaftEngine, ALL_CFS, CF_DEFAULT, CF_WRITE,
 };
 use error_code::ErrorCodeExt;
+use fail::fail_point;
 use file_system::{
     set_io_rate_limiter, BytesFetcher, IORateLimiter, MetricsManager as IOMetricsManager,
 };
@@ -81,9 +85,11 @@ use tikv::{
 use tikv_util::{
     check_environment_variables,
     config::{ensure_dir_exist, VersionTrack},
-    sys::sys_quota::SysQuota,
+    sys::{disk, sys_quota::SysQuota},
     time::Monitor,
-    worker::{Builder as WorkerBuilder, FutureWorker, LazyWorker, Worker},
+    worker::{
+        Builder as WorkerBuilder, FutureWorker, LazyWorker, Runnable, RunnableWithTimer, Worker,
+    },
 };
 use tokio::runtime::Builder;
 
@@ -92,6 +98,474 @@ use crate::{setup::*, signal_handler};
 
 const GBSIZE: u64 = 1024 * 1024 * 1024;
 
+/// A central registry of the background workers spun up while bootstrapping
+/// a `TiKVServer`, so their liveness can be inspected and controlled at
+/// runtime instead of only through log-grepping.
+///
+/// Every `Worker`/`LazyWorker`/`FutureWorker` created in this module should
+/// call [`WorkerRegistry::register`] right after construction. `register`
+/// returns the worker's shared [`WorkerStatus`] cell; the call sites in this
+/// module discard it, so no run loop here reports `Active`/`Idle`/`Dead`
+/// transitions and every worker registered here stays in its initial `Idle`
+/// state. The registry itself is handed to the status server and debug
+/// service so operators can list workers and control the ones registered
+/// with a [`WorkerControl`] (`pause`/`resume`/`trigger_now`) without
+/// restarting the node.
+pub mod worker_registry {
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        time::Instant,
+    };
+
+    use serde::Serialize;
+
+    /// Liveness state of a registered worker, as last reported by its run loop.
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    #[serde(tag = "state", content = "reason", rename_all = "snake_case")]
+    pub enum WorkerState {
+        /// Currently processing a task.
+        Active,
+        /// Alive but has nothing to do right now.
+        Idle,
+        /// The worker's run loop has exited, with the reason it stopped.
+        Dead(String),
+    }
+
+    /// Verbs a registered worker can be asked to perform remotely.
+    ///
+    /// Workers that have no meaningful notion of one of these (e.g. a worker
+    /// that can't be pause is fine to no-op) should just implement it as a
+    /// no-op rather than erroring, so the control surface stays uniform.
+    pub trait WorkerControl: Send + Sync {
+        fn pause(&self) {}
+        fn resume(&self) {}
+        fn trigger_now(&self) {}
+    }
+
+    /// The shared status cell updated by a worker's run loop.
+    pub struct WorkerStatus {
+        pub name: String,
+        pub category: &'static str,
+        state: Mutex<WorkerState>,
+        last_tick: Mutex<Instant>,
+        iteration_count: AtomicU64,
+        error_count: AtomicU64,
+        last_error: Mutex<Option<String>>,
+    }
+
+    impl WorkerStatus {
+        pub fn set_active(&self) {
+            *self.state.lock().unwrap() = WorkerState::Active;
+            *self.last_tick.lock().unwrap() = Instant::now();
+            self.iteration_count.fetch_add(1, Ordering::Relaxed);
+        }
+
+        pub fn set_idle(&self) {
+            *self.state.lock().unwrap() = WorkerState::Idle;
+            *self.last_tick.lock().unwrap() = Instant::now();
+        }
+
+        pub fn set_dead(&self, reason: impl Into<String>) {
+            *self.state.lock().unwrap() = WorkerState::Dead(reason.into());
+        }
+
+        pub fn record_error(&self, err: impl Into<String>) {
+            self.error_count.fetch_add(1, Ordering::Relaxed);
+            *self.last_error.lock().unwrap() = Some(err.into());
+        }
+    }
+
+    /// A point-in-time view of a worker's status, suitable for serializing to
+    /// the status server's `/workers` endpoint. `idle_for_secs` is derived
+    /// from `last_tick` at snapshot time since a raw `Instant` carries no
+    /// fixed epoch and can't be serialized as-is.
+    #[derive(Clone, Debug, Serialize)]
+    pub struct WorkerSnapshot {
+        pub name: String,
+        pub category: &'static str,
+        pub state: WorkerState,
+        pub idle_for_secs: f64,
+        pub iteration_count: u64,
+        pub error_count: u64,
+        pub last_error: Option<String>,
+    }
+
+    struct RegisteredWorker {
+        status: Arc<WorkerStatus>,
+        control: Option<Box<dyn WorkerControl>>,
+    }
+
+    /// Central registry of all background workers owned by a `TiKVServer`.
+    #[derive(Default)]
+    pub struct WorkerRegistry {
+        workers: Mutex<HashMap<String, RegisteredWorker>>,
+    }
+
+    impl WorkerRegistry {
+        pub fn new() -> Self {
+            WorkerRegistry::default()
+        }
+
+        /// Registers a new worker under `name`/`category`, returning the shared
+        /// status cell the worker's run loop should update as it makes
+        /// progress. `control` is optional: workers with no natural
+        /// pause/resume/trigger semantics can pass `None`.
+        pub fn register(
+            &self,
+            name: impl Into<String>,
+            category: &'static str,
+            control: Option<Box<dyn WorkerControl>>,
+        ) -> Arc<WorkerStatus> {
+            let name = name.into();
+            let status = Arc::new(WorkerStatus {
+                name: name.clone(),
+                category,
+                state: Mutex::new(WorkerState::Idle),
+                last_tick: Mutex::new(Instant::now()),
+                iteration_count: AtomicU64::new(0),
+                error_count: AtomicU64::new(0),
+                last_error: Mutex::new(None),
+            });
+            self.workers.lock().unwrap().insert(
+                name,
+                RegisteredWorker {
+                    status: status.clone(),
+                    control,
+                },
+            );
+            status
+        }
+
+        /// Returns a snapshot of every registered worker's current state, for
+        /// the status server's `/workers` endpoint.
+        pub fn snapshot(&self) -> Vec<WorkerSnapshot> {
+            self.workers
+                .lock()
+                .unwrap()
+                .values()
+                .map(|w| WorkerSnapshot {
+                    name: w.status.name.clone(),
+                    category: w.status.category,
+                    state: w.status.state.lock().unwrap().clone(),
+                    idle_for_secs: w.status.last_tick.lock().unwrap().elapsed().as_secs_f64(),
+                    iteration_count: w.status.iteration_count.load(Ordering::Relaxed),
+                    error_count: w.status.error_count.load(Ordering::Relaxed),
+                    last_error: w.status.last_error.lock().unwrap().clone(),
+                })
+                .collect()
+        }
+
+        pub fn pause(&self, name: &str) -> bool {
+            self.with_control(name, |c| c.pause())
+        }
+
+        pub fn resume(&self, name: &str) -> bool {
+            self.with_control(name, |c| c.resume())
+        }
+
+        pub fn trigger_now(&self, name: &str) -> bool {
+            self.with_control(name, |c| c.trigger_now())
+        }
+
+        fn with_control(&self, name: &str, f: impl FnOnce(&dyn WorkerControl)) -> bool {
+            let workers = self.workers.lock().unwrap();
+            match workers.get(name).and_then(|w| w.control.as_deref()) {
+                Some(control) => {
+                    f(control);
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+}
+
+use worker_registry::{WorkerControl, WorkerRegistry};
+
+impl WorkerControl for data_scrub::ScrubHandle {
+    fn pause(&self) {
+        data_scrub::ScrubHandle::pause(self);
+    }
+
+    fn resume(&self) {
+        data_scrub::ScrubHandle::resume(self);
+    }
+
+    fn trigger_now(&self) {
+        self.start();
+    }
+}
+
+/// An always-on background scrubber that sweeps region data/write CFs
+/// looking for silent corruption (checksum mismatches, undecodable keys),
+/// throttled by a runtime-tunable "tranquility" knob so it doesn't compete
+/// with foreground traffic.
+///
+/// Progress is periodically persisted to a small state file under
+/// `storage.data_dir` so a restart resumes mid-pass instead of rescanning
+/// from the start. The actual per-region check is delegated to the
+/// [`RegionScrubber`] implementation passed to [`ScrubWorker::run`]; it is
+/// not registered with the `CoprocessorHost`. This module only owns the
+/// sweep order, throttling and persistence.
+pub mod data_scrub {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use file_system::{IOType, WithIOType};
+    use serde::{Deserialize, Serialize};
+
+    /// Outcome of scrubbing one batch of keys in a region.
+    pub struct ScrubBatchResult {
+        pub keys_checked: u64,
+        pub corruptions_found: u64,
+        pub bytes_read: u64,
+        /// `None` once the region has been fully swept.
+        pub next_key: Option<Vec<u8>>,
+    }
+
+    /// Pluggable per-region check, implemented by whatever owns the engine CFs
+    /// and handed directly to [`ScrubWorker::run`].
+    pub trait RegionScrubber: Send {
+        fn scrub_batch(
+            &mut self,
+            region_id: u64,
+            start_key: &[u8],
+            batch_size: usize,
+        ) -> ScrubBatchResult;
+
+        /// Ids of all regions known to this store, in a stable sweep order.
+        fn region_ids(&self) -> Vec<u64>;
+    }
+
+    #[derive(Serialize, Deserialize, Default, Clone)]
+    pub struct ScrubProgress {
+        pub last_region_id: u64,
+        pub last_key: Vec<u8>,
+        pub last_full_pass_completed_unix_secs: Option<u64>,
+        pub keys_checked: u64,
+        pub corruption_count: u64,
+        pub bytes_read: u64,
+    }
+
+    impl ScrubProgress {
+        fn load(path: &Path) -> Self {
+            fs::read(path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .unwrap_or_default()
+        }
+
+        fn persist(&self, path: &Path) {
+            if let Ok(bytes) = serde_json::to_vec(self) {
+                // Best-effort: a missed persist just costs a re-scan of the
+                // current region after a crash, not correctness.
+                let _ = fs::write(path, bytes);
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    enum ScrubCommand {
+        Start,
+        Pause,
+        Resume,
+        Cancel,
+        SetTranquility(u8),
+    }
+
+    const SCRUB_BATCH_SIZE: usize = 256;
+    const SCRUB_PROGRESS_FILE: &str = "scrub_progress.json";
+    /// How many batches elapse between progress persists. Persisting every
+    /// batch makes the scrub needlessly IO-heavy; a crash just costs re-
+    /// scanning up to this many batches of the current region.
+    const PERSIST_EVERY_N_BATCHES: u32 = 8;
+    /// Tranquility is a percentage in `[0, 99]`; 100 would mean "never make
+    /// progress", so larger values are clamped to this rather than accepted
+    /// and hung forever.
+    const MAX_TRANQUILITY_PCT: u8 = 99;
+
+    /// A cloneable remote control for a running [`ScrubWorker`]: start/pause/
+    /// resume/cancel it and read/write its tranquility, all from any thread
+    /// (the worker registry's control verbs, and the dedicated scrub control
+    /// surface hung off `TiKVServer::scrub_handle`).
+    #[derive(Clone)]
+    pub struct ScrubHandle {
+        tranquility_pct: std::sync::Arc<std::sync::atomic::AtomicU32>,
+        cmd_tx: std::sync::mpsc::Sender<ScrubCommand>,
+    }
+
+    impl ScrubHandle {
+        /// `tranquility_pct` is the percentage in `[0, 99]` of the worker's
+        /// wall time spent idling (see [`ScrubWorker`] for the resulting
+        /// sleep after each batch); values above the max are
+        /// clamped rather than rejected, since this is a runtime throttle
+        /// knob operators may fat-finger under pressure.
+        pub fn set_tranquility(&self, tranquility_pct: u8) {
+            let clamped = tranquility_pct.min(MAX_TRANQUILITY_PCT);
+            self.tranquility_pct
+                .store(clamped as u32, std::sync::atomic::Ordering::Relaxed);
+            let _ = self.cmd_tx.send(ScrubCommand::SetTranquility(clamped));
+        }
+
+        pub fn tranquility(&self) -> u8 {
+            self.tranquility_pct
+                .load(std::sync::atomic::Ordering::Relaxed) as u8
+        }
+
+        pub fn start(&self) {
+            let _ = self.cmd_tx.send(ScrubCommand::Start);
+        }
+
+        pub fn pause(&self) {
+            let _ = self.cmd_tx.send(ScrubCommand::Pause);
+        }
+
+        pub fn resume(&self) {
+            let _ = self.cmd_tx.send(ScrubCommand::Resume);
+        }
+
+        pub fn cancel(&self) {
+            let _ = self.cmd_tx.send(ScrubCommand::Cancel);
+        }
+    }
+
+    /// Runtime-tunable pacing knob: after each batch the worker sleeps
+    /// `tranquility / (1 - tranquility) * elapsed_batch_time`, where
+    /// `tranquility` is the `tranquility_pct` percentage expressed as a
+    /// ratio in `[0, 0.99]`. At `tranquility_pct == 0` the scrub runs flat
+    /// out; as it approaches 99 the worker spends almost all of its time
+    /// idling, trading scrub speed for foreground impact.
+    pub struct ScrubWorker {
+        tranquility_pct: std::sync::Arc<std::sync::atomic::AtomicU32>,
+        cmd_rx: std::sync::mpsc::Receiver<ScrubCommand>,
+        progress_path: PathBuf,
+        progress: ScrubProgress,
+    }
+
+    impl ScrubWorker {
+        pub fn new(data_dir: impl AsRef<Path>, tranquility_pct: u8) -> (Self, ScrubHandle) {
+            let (cmd_tx, cmd_rx) = std::sync::mpsc::channel();
+            let progress_path = data_dir.as_ref().join(SCRUB_PROGRESS_FILE);
+            let progress = ScrubProgress::load(&progress_path);
+            let tranquility_pct = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(
+                tranquility_pct.min(MAX_TRANQUILITY_PCT) as u32,
+            ));
+            let worker = ScrubWorker {
+                tranquility_pct: tranquility_pct.clone(),
+                cmd_rx,
+                progress_path,
+                progress,
+            };
+            let handle = ScrubHandle {
+                tranquility_pct,
+                cmd_tx,
+            };
+            (worker, handle)
+        }
+
+        fn sleep_for_tranquility(&self, elapsed: Duration) {
+            let pct = self
+                .tranquility_pct
+                .load(std::sync::atomic::Ordering::Relaxed) as f64;
+            if pct <= 0.0 {
+                return;
+            }
+            let ratio = pct / 100.0;
+            let sleep_secs = (ratio / (1.0 - ratio)) * elapsed.as_secs_f64();
+            thread::sleep(Duration::from_secs_f64(sleep_secs));
+        }
+
+        /// Sweeps every region in repeated full passes until cancelled,
+        /// running on a dedicated OS thread so the CPU-heavy checksum
+        /// verification never starves the async runtimes. Commands sent
+        /// through the [`ScrubHandle`] are picked up between passes.
+        pub fn run(&mut self, scrubber: &mut dyn RegionScrubber) {
+            let _io_type_guard = WithIOType::new(IOType::Other);
+            let mut paused = false;
+            let mut batches_since_persist = 0u32;
+            loop {
+                match self.cmd_rx.try_recv() {
+                    Ok(ScrubCommand::Pause) => paused = true,
+                    Ok(ScrubCommand::Start) | Ok(ScrubCommand::Resume) => paused = false,
+                    Ok(ScrubCommand::Cancel) => return,
+                    Ok(ScrubCommand::SetTranquility(pct)) => {
+                        self.tranquility_pct
+                            .store(pct as u32, std::sync::atomic::Ordering::Relaxed);
+                    }
+                    Err(_) => {}
+                }
+                if paused {
+                    thread::sleep(Duration::from_millis(200));
+                    continue;
+                }
+
+                let regions = scrubber.region_ids();
+                if regions.is_empty() {
+                    thread::sleep(Duration::from_secs(1));
+                    continue;
+                }
+
+                // A pass interrupted by a restart skips the regions before the
+                // recorded one and continues that one from its recorded key.
+                let mut resume_region = Some(self.progress.last_region_id)
+                    .filter(|id| regions.contains(id));
+                for region_id in regions {
+                    if resume_region.map_or(false, |id| id != region_id) {
+                        continue;
+                    }
+                    let mut start_key = if resume_region.take().is_some() {
+                        self.progress.last_key.clone()
+                    } else {
+                        Vec::new()
+                    };
+                    loop {
+                        let batch_start = Instant::now();
+                        let result = scrubber.scrub_batch(region_id, &start_key, SCRUB_BATCH_SIZE);
+                        let elapsed = batch_start.elapsed();
+
+                        self.progress.last_region_id = region_id;
+                        self.progress.last_key = start_key.clone();
+                        self.progress.keys_checked += result.keys_checked;
+                        self.progress.corruption_count += result.corruptions_found;
+                        self.progress.bytes_read += result.bytes_read;
+                        batches_since_persist += 1;
+                        if batches_since_persist >= PERSIST_EVERY_N_BATCHES {
+                            self.progress.persist(&self.progress_path);
+                            batches_since_persist = 0;
+                        }
+
+                        self.sleep_for_tranquility(elapsed);
+
+                        match result.next_key {
+                            Some(next) => start_key = next,
+                            None => break,
+                        }
+                    }
+                }
+                self.progress.last_full_pass_completed_unix_secs = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .ok()
+                    .map(|d| d.as_secs());
+                self.progress.last_region_id = 0;
+                self.progress.last_key.clear();
+                self.progress.persist(&self.progress_path);
+            }
+        }
+
+        pub fn progress(&self) -> ScrubProgress {
+            self.progress.clone()
+        }
+    }
+}
+
 /// Run a TiKV server. Returns when the server is shutdown by the user, in which
 /// case the server will be properly stopped.
 pub fn run_tikv(config: TiKvConfig) {
@@ -146,6 +620,9 @@ const RESERVED_OPEN_FDS: u64 = 1000;
 
 const DEFAULT_METRICS_FLUSH_INTERVAL: Duration = Duration::from_millis(10_000);
 const DEFAULT_STORAGE_STATS_INTERVAL: Duration = Duration::from_secs(10);
+// Floor between emergency reclamation passes, so a flapping tier can't
+// re-trigger raft-log GC / compaction back-to-back.
+const DEFAULT_EMERGENCY_RECLAMATION_INTERVAL: Duration = Duration::from_secs(60);
 
 /// A complete TiKV server.
 struct TiKVServer<ER: RaftEngine> {
@@ -171,6 +648,9 @@ struct TiKVServer<ER: RaftEngine> {
     env: Arc<Environment>,
     background_worker: Worker,
     write_permission: Arc<Mutex<bool>>,
+    worker_registry: Arc<WorkerRegistry>,
+    scrub_handle: Option<data_scrub::ScrubHandle>,
+    reclamation_guard: Option<disk::DiskStateListenerGuard>,
 }
 
 struct TiKVEngines<ER: RaftEngine> {
@@ -179,6 +659,105 @@ struct TiKVEngines<ER: RaftEngine> {
     engine: RaftKv<RocksEngine, ServerRaftStoreRouter<RocksEngine, ER>>,
 }
 
+/// The [`data_scrub::RegionScrubber`] driving the always-on background
+/// scrub: `region_ids` reads the live region set straight out of
+/// `store_meta` at the start of every pass rather than a snapshot taken at
+/// startup, so splits/merges that happen mid-sweep are picked up by the next
+/// pass, and
+/// `scrub_batch` actually reads `CF_DEFAULT`/`CF_WRITE` within each
+/// region's key range rather than no-op'ing.
+///
+/// Decoding every `CF_WRITE` value with `storage::mvcc::Write::parse` is
+/// the corruption signal: a write record's on-disk format is itself a
+/// checked invariant, so a decode failure there is real evidence of
+/// corruption independent of any raft-level digest exchange (which would
+/// require routing through the propose/apply path this background thread
+/// doesn't have access to).
+struct StoreScrubber {
+    store_meta: Arc<Mutex<StoreMeta>>,
+    engine: RocksEngine,
+}
+
+impl data_scrub::RegionScrubber for StoreScrubber {
+    fn region_ids(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .store_meta
+            .lock()
+            .unwrap()
+            .regions
+            .keys()
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn scrub_batch(
+        &mut self,
+        region_id: u64,
+        start_key: &[u8],
+        batch_size: usize,
+    ) -> data_scrub::ScrubBatchResult {
+        let region = match self
+            .store_meta
+            .lock()
+            .unwrap()
+            .regions
+            .get(&region_id)
+            .cloned()
+        {
+            Some(region) => region,
+            // Split/merged/moved away between sweep ticks: nothing left to
+            // scrub here this pass, the next sweep picks up whatever ids
+            // are current.
+            None => {
+                return data_scrub::ScrubBatchResult {
+                    keys_checked: 0,
+                    corruptions_found: 0,
+                    bytes_read: 0,
+                    next_key: None,
+                };
+            }
+        };
+        let end_key = region.get_end_key().to_vec();
+        let snap = self.engine.snapshot();
+        let mut keys_checked = 0u64;
+        let mut corruptions_found = 0u64;
+        let mut bytes_read = 0u64;
+        let mut next_key = None;
+        'cfs: for cf in [CF_DEFAULT, CF_WRITE] {
+            let mut iter = match snap.iterator_cf(cf, IterOptions::default()) {
+                Ok(iter) => iter,
+                Err(_) => continue,
+            };
+            let mut valid = iter.seek(start_key).unwrap_or(false);
+            while valid {
+                let key = iter.key();
+                if !end_key.is_empty() && key >= end_key.as_slice() {
+                    break;
+                }
+                if keys_checked >= batch_size as u64 {
+                    next_key = Some(key.to_vec());
+                    break 'cfs;
+                }
+                let value = iter.value();
+                bytes_read += (key.len() + value.len()) as u64;
+                keys_checked += 1;
+                if cf == CF_WRITE && storage::mvcc::Write::parse(value).is_err() {
+                    corruptions_found += 1;
+                }
+                valid = iter.next().unwrap_or(false);
+            }
+        }
+        data_scrub::ScrubBatchResult {
+            keys_checked,
+            corruptions_found,
+            bytes_read,
+            next_key,
+        }
+    }
+}
+
 struct Servers<ER: RaftEngine> {
     lock_mgr: LockManager,
     server: Server<RaftRouter<RocksEngine, ER>, resolve::PdStoreAddrResolver>,
@@ -214,10 +793,13 @@ impl<ER: RaftEngine> TiKVServer<ER> {
         // Initialize raftstore channels.
         let (router, system) = fsm::create_raft_batch_system(&config.raft_store);
 
+        let worker_registry = Arc::new(WorkerRegistry::new());
+
         let thread_count = config.server.background_thread_count;
         let background_worker = WorkerBuilder::new("background")
             .thread_count(thread_count)
             .create();
+        worker_registry.register("background", "core", None);
         let (resolver, state) =
             resolve::new_resolver(Arc::clone(&pd_client), &background_worker, router.clone());
 
@@ -253,6 +835,9 @@ impl<ER: RaftEngine> TiKVServer<ER> {
             env,
             background_worker,
             write_permission: Arc::new(Mutex::new(true)),
+            worker_registry,
+            scrub_handle: None,
+            reclamation_guard: None,
         }
     }
 
@@ -268,6 +853,15 @@ impl<ER: RaftEngine> TiKVServer<ER> {
     /// - If the max open file descriptor limit is not high enough to support
     ///   the main database and the raft database.
     fn init_config(mut config: TiKvConfig) -> ConfigController {
+        if let Ok(dir) = env::var(CONFIG_FRAGMENT_DIR_ENV) {
+            let dir = PathBuf::from(dir);
+            if dir.is_dir() {
+                config = load_config_fragments(config, &dir);
+            } else {
+                warn!("config-dir does not exist, ignoring"; "dir" => %dir.display());
+            }
+        }
+
         validate_and_persist_config(&mut config, true);
 
         ensure_dir_exist(&config.storage.data_dir).unwrap();
@@ -493,6 +1087,7 @@ impl<ER: RaftEngine> TiKVServer<ER> {
         gc_worker
             .start()
             .unwrap_or_else(|e| fatal!("failed to start gc worker: {}", e));
+        self.worker_registry.register("gc-worker", "gc", None);
         gc_worker
             .start_observe_lock_apply(
                 self.coprocessor_host.as_mut().unwrap(),
@@ -509,10 +1104,39 @@ impl<ER: RaftEngine> TiKVServer<ER> {
         gc_worker
     }
 
+    /// Starts the always-on background data scrubber (see [`data_scrub`]) on
+    /// a dedicated OS thread, and registers it in the worker registry so it
+    /// can be paused/resumed/triggered from the status server.
+    fn init_scrub_worker(&mut self) {
+        // 66% tranquility: for every second spent scrubbing, idle for two,
+        // keeping foreground impact low by default.
+        const DEFAULT_SCRUB_TRANQUILITY_PCT: u8 = 66;
+        let (mut worker, handle) = data_scrub::ScrubWorker::new(
+            &self.config.storage.data_dir,
+            DEFAULT_SCRUB_TRANQUILITY_PCT,
+        );
+        self.worker_registry
+            .register("data-scrub", "consistency", Some(Box::new(handle.clone())));
+        handle.start();
+        self.scrub_handle = Some(handle);
+
+        let store_meta = self.engines.as_ref().unwrap().store_meta.clone();
+        let engine = self.engines.as_ref().unwrap().engines.kv.clone();
+        thread::Builder::new()
+            .name(thd_name!("data-scrub"))
+            .spawn(move || {
+                let mut scrubber = StoreScrubber { store_meta, engine };
+                worker.run(&mut scrubber);
+            })
+            .unwrap_or_else(|e| fatal!("failed to spawn data-scrub thread: {}", e));
+    }
+
     fn init_servers(&mut self) -> Arc<VersionTrack<ServerConfig>> {
         let gc_worker = self.init_gc_worker();
         let mut ttl_checker = Box::new(LazyWorker::new("ttl-checker"));
         let ttl_scheduler = ttl_checker.scheduler();
+        self.worker_registry
+            .register("ttl-checker", "storage", None);
 
         let cfg_controller = self.cfg_controller.as_mut().unwrap();
         cfg_controller.register(
@@ -527,6 +1151,7 @@ impl<ER: RaftEngine> TiKVServer<ER> {
         // Create cdc.
         let mut cdc_worker = Box::new(LazyWorker::new("cdc"));
         let cdc_scheduler = cdc_worker.scheduler();
+        self.worker_registry.register("cdc", "replication", None);
         let txn_extra_scheduler = cdc::CdcTxnExtraScheduler::new(cdc_scheduler.clone());
 
         self.engines
@@ -546,6 +1171,7 @@ impl<ER: RaftEngine> TiKVServer<ER> {
 
         let pd_worker = FutureWorker::new("pd-worker");
         let pd_sender = pd_worker.scheduler();
+        self.worker_registry.register("pd-worker", "core", None);
 
         let unified_read_pool = if self.config.readpool.is_unified_pool_enabled() {
             Some(build_yatp_read_pool(
@@ -620,6 +1246,8 @@ impl<ER: RaftEngine> TiKVServer<ER> {
         // Create resolved ts worker
         let mut rts_worker = Box::new(LazyWorker::new("resolved-ts"));
         let rts_scheduler = rts_worker.scheduler();
+        self.worker_registry
+            .register("resolved-ts", "replication", None);
 
         // Register cdc
         let cdc_ob = cdc::CdcObserver::new(cdc_scheduler.clone());
@@ -643,6 +1271,7 @@ impl<ER: RaftEngine> TiKVServer<ER> {
             self.state.clone(),
             self.background_worker.clone(),
         );
+        fail_point!("before_try_bootstrap_store");
         node.try_bootstrap_store(engines.engines.clone())
             .unwrap_or_else(|e| fatal!("failed to bootstrap node id: {}", e));
 
@@ -694,6 +1323,8 @@ impl<ER: RaftEngine> TiKVServer<ER> {
         let split_check_scheduler = self
             .background_worker
             .start("split-check", split_check_runner);
+        self.worker_registry
+            .register("split-check", "raftstore", None);
         cfg_controller.register(
             tikv::config::Module::Coprocessor,
             Box::new(SplitCheckConfigManager(split_check_scheduler.clone())),
@@ -729,6 +1360,9 @@ impl<ER: RaftEngine> TiKVServer<ER> {
             .registry
             .register_consistency_check_observer(100, observer);
 
+        self.init_scrub_worker();
+
+        fail_point!("before_node_start");
         node.start(
             engines.engines.clone(),
             server.transport(),
@@ -750,6 +1384,7 @@ impl<ER: RaftEngine> TiKVServer<ER> {
             self.region_info_accessor.clone(),
             node.id(),
         );
+        fail_point!("before_start_auto_gc");
         if let Err(e) = gc_worker.start_auto_gc(auto_gc_config, safe_point) {
             fatal!("failed to start auto_gc on storage, error: {}", e);
         }
@@ -806,6 +1441,7 @@ impl<ER: RaftEngine> TiKVServer<ER> {
     }
 
     fn register_services(&mut self) {
+        fail_point!("before_register_services");
         let servers = self.servers.as_mut().unwrap();
         let engines = self.engines.as_ref().unwrap();
 
@@ -824,12 +1460,18 @@ impl<ER: RaftEngine> TiKVServer<ER> {
             fatal!("failed to register import service");
         }
 
-        // Debug service.
+        // Debug service. Besides the usual engine/cfg-controller handles, this also
+        // hands the service the live `router` and `store_meta` so its region-router
+        // introspection RPC (mailbox depth, leader/applied/committed indexes, and the
+        // store-wide "dump all regions" summary) can read `TiKVServer`'s in-memory
+        // raftstore state directly instead of operators having to grep logs.
         let debug_service = DebugService::new(
             engines.engines.clone(),
+            engines.store_meta.clone(),
             servers.server.get_debug_thread_pool().clone(),
             self.router.clone(),
             self.cfg_controller.as_ref().unwrap().clone(),
+            self.worker_registry.clone(),
         );
         if servers
             .server
@@ -876,6 +1518,8 @@ impl<ER: RaftEngine> TiKVServer<ER> {
         // Backup service.
         let mut backup_worker = Box::new(self.background_worker.lazy_build("backup-endpoint"));
         let backup_scheduler = backup_worker.scheduler();
+        self.worker_registry
+            .register("backup-endpoint", "backup", None);
         let backup_service = backup::Service::new(backup_scheduler);
         if servers
             .server
@@ -930,8 +1574,16 @@ impl<ER: RaftEngine> TiKVServer<ER> {
     }
 
     fn init_metrics_flusher(&mut self, fetcher: BytesFetcher) {
-        let mut engine_metrics =
-            EngineMetricsManager::new(self.engines.as_ref().unwrap().engines.clone());
+        let engine_metrics = EngineMetricsManager::new(
+            self.engines.as_ref().unwrap().engines.clone(),
+            EngineMetricsConfig::default(),
+        );
+        let mut engine_metrics_worker = Box::new(LazyWorker::new("engine-metrics"));
+        engine_metrics_worker.start_with_timer(engine_metrics);
+        self.worker_registry
+            .register("engine-metrics", "storage", None);
+        self.to_stop.push(engine_metrics_worker);
+
         let mut io_metrics = IOMetricsManager::new(fetcher);
         let mut last_call = Instant::now();
         self.background_worker
@@ -939,15 +1591,49 @@ impl<ER: RaftEngine> TiKVServer<ER> {
                 let now = Instant::now();
                 let duration = now - last_call;
                 last_call = now;
-                engine_metrics.flush(duration);
                 io_metrics.flush(duration);
             });
     }
-    fn init_storage_stats_task(&self, engines: Engines<RocksEngine, ER>) {
+    fn init_storage_stats_task(&mut self, engines: Engines<RocksEngine, ER>) {
         let flag = self.write_permission.clone();
         let config_disk_capacity: u64 = self.config.raft_store.capacity.0;
+        // Thresholds for the `Normal -> AlmostFull -> AlreadyFull` disk-pressure
+        // state machine; `disk_recovery_margin_percent` is the low-water mark
+        // applied on de-escalation so the tier doesn't flap right at the
+        // boundary.
+        let almost_full_percent = self.config.raft_store.almost_full_percent;
+        let already_full_percent = self.config.raft_store.already_full_percent;
+        let recovery_margin_percent = self.config.raft_store.disk_recovery_margin_percent;
         let store_path = self.store_path.clone();
         let snap_path = self.snap_path.clone();
+
+        // Kick an emergency reclamation pass as soon as the tier below
+        // crosses into pressure, rather than waiting on this same tick's
+        // next background raft-log-gc/compaction schedule. Rate-limited so
+        // a tier that flaps between `AlmostFull` and `Normal` can't fire it
+        // more than once per `DEFAULT_EMERGENCY_RECLAMATION_INTERVAL`.
+        //
+        // The raft-log GC scheduler this should also notify isn't part of
+        // this crate's trimmed build, so this only drives the RocksDB half:
+        // a manual compaction of every CF, which is the actual mechanism
+        // that reclaims space once raft-log GC has dropped the keys.
+        let reclamation_engine = engines.kv.clone();
+        self.reclamation_guard = Some(disk::register_emergency_reclamation(
+            DEFAULT_EMERGENCY_RECLAMATION_INTERVAL,
+            move || {
+                warn!("disk usage under pressure, triggering emergency reclamation pass");
+                for cf in ALL_CFS {
+                    if let Err(e) = reclamation_engine.compact_range_cf(cf, None, None, false, 1) {
+                        error!(
+                            "emergency reclamation compaction failed";
+                            "cf" => cf,
+                            "err" => ?e,
+                        );
+                    }
+                }
+            },
+        ));
+
         self.background_worker
             .spawn_interval_task(DEFAULT_STORAGE_STATS_INTERVAL, move || {
                 let disk_stats = match fs2::statvfs(&store_path) {
@@ -1000,23 +1686,59 @@ impl<ER: RaftEngine> TiKVServer<ER> {
                 } else {
                     config_disk_capacity
                 };
-                if total_used * 100 / capacity >= 95 {
+                let used_percent = total_used * 100 / capacity;
+                let current_tier = disk::get_disk_usage();
+                let next_tier = match current_tier {
+                    disk::DiskUsage::AlreadyFull => {
+                        if used_percent + recovery_margin_percent < already_full_percent {
+                            disk::DiskUsage::AlmostFull
+                        } else {
+                            disk::DiskUsage::AlreadyFull
+                        }
+                    }
+                    disk::DiskUsage::AlmostFull => {
+                        if used_percent >= already_full_percent {
+                            disk::DiskUsage::AlreadyFull
+                        } else if used_percent + recovery_margin_percent < almost_full_percent {
+                            disk::DiskUsage::Normal
+                        } else {
+                            disk::DiskUsage::AlmostFull
+                        }
+                    }
+                    disk::DiskUsage::Normal => {
+                        if used_percent >= already_full_percent {
+                            disk::DiskUsage::AlreadyFull
+                        } else if used_percent >= almost_full_percent {
+                            disk::DiskUsage::AlmostFull
+                        } else {
+                            disk::DiskUsage::Normal
+                        }
+                    }
+                };
+                if next_tier != current_tier {
                     warn!(
-                        "disk usage threshold：total used {:?}, config cap={:?}, disk available={:?}",
-                        total_used / GBSIZE,
-                        config_disk_capacity / GBSIZE,
-                        disk_available/GBSIZE
+                        "disk usage tier changed";
+                        "from" => ?current_tier,
+                        "to" => ?next_tier,
+                        "used_percent" => used_percent,
+                        "total_used_gb" => total_used / GBSIZE,
+                        "config_cap_gb" => config_disk_capacity / GBSIZE,
+                        "disk_available_gb" => disk_available / GBSIZE,
                     );
-                    let mut f = flag.lock().unwrap();
-                    *f = false;
-                } else {
-                    let mut f = flag.lock().unwrap();
-                    *f = true;
+                }
+                disk::set_disk_usage(next_tier);
+                *flag.lock().unwrap() = next_tier != disk::DiskUsage::AlreadyFull;
+
+                if disk::is_leader_drain_requested() {
+                    // Only the drain request is logged here: this task
+                    // transfers no region leader and sets no flag on the
+                    // PD store heartbeat.
+                    warn!("store disk usage already full, requesting leader drain");
                 }
 
                 warn!(
-                    "disk capacity checking, disk capacity={:?},kv_size={:?},snap_size={:?},config-cap={:?},flag{:?}",
-                    disk_cap, kv_size,   snap_size,config_disk_capacity,flag
+                    "disk capacity checking, disk capacity={:?},kv_size={:?},snap_size={:?},config-cap={:?},tier={:?}",
+                    disk_cap, kv_size, snap_size, config_disk_capacity, next_tier
                 );
             })
     }
@@ -1043,6 +1765,7 @@ impl<ER: RaftEngine> TiKVServer<ER> {
                 self.cfg_controller.take().unwrap(),
                 Arc::new(self.config.security.clone()),
                 self.router.clone(),
+                self.worker_registry.clone(),
             ) {
                 Ok(status_server) => Box::new(status_server),
                 Err(e) => {
@@ -1083,6 +1806,7 @@ impl TiKVServer<RocksEngine> {
         &mut self,
         limiter: Option<Arc<IORateLimiter>>,
     ) -> Engines<RocksEngine, RocksEngine> {
+        fail_point!("before_init_raw_engines");
         let env =
             get_encrypted_env(self.encryption_key_manager.clone(), None /*base_env*/).unwrap();
         let env = get_inspected_env(Some(env), limiter).unwrap();
@@ -1092,6 +1816,7 @@ impl TiKVServer<RocksEngine> {
         let raft_db_path = Path::new(&self.config.raft_store.raftdb_path);
         let config_raftdb = &self.config.raftdb;
         let mut raft_db_opts = config_raftdb.build_opt();
+        apply_extra_db_options(&mut raft_db_opts, &config_raftdb.extra_options, "raftdb");
         raft_db_opts.set_env(env.clone());
         let raft_db_cf_opts = config_raftdb.build_cf_opts(&block_cache);
         let raft_engine = engine_rocks::raw_util::new_engine_opt(
@@ -1103,6 +1828,11 @@ impl TiKVServer<RocksEngine> {
 
         // Create kv engine.
         let mut kv_db_opts = self.config.rocksdb.build_opt();
+        apply_extra_db_options(
+            &mut kv_db_opts,
+            &self.config.rocksdb.extra_options,
+            "rocksdb",
+        );
         kv_db_opts.set_env(env);
         kv_db_opts.add_event_listener(self.create_raftstore_compaction_listener());
         let kv_cfs_opts = self.config.rocksdb.build_cf_opts(
@@ -1125,6 +1855,7 @@ impl TiKVServer<RocksEngine> {
         raft_engine.set_shared_block_cache(shared_block_cache);
         let engines = Engines::new(kv_engine, raft_engine);
 
+        fail_point!("before_check_and_dump_raft_engine");
         check_and_dump_raft_engine(&self.config, &engines.raft, 8);
 
         let cfg_controller = self.cfg_controller.as_mut().unwrap();
@@ -1154,6 +1885,7 @@ impl TiKVServer<RaftLogEngine> {
         &mut self,
         limiter: Option<Arc<IORateLimiter>>,
     ) -> Engines<RocksEngine, RaftLogEngine> {
+        fail_point!("before_init_raw_engines");
         let env =
             get_encrypted_env(self.encryption_key_manager.clone(), None /*base_env*/).unwrap();
         let env = get_inspected_env(Some(env), limiter).unwrap();
@@ -1164,10 +1896,16 @@ impl TiKVServer<RaftLogEngine> {
         let raft_engine = RaftLogEngine::new(raft_config);
 
         // Try to dump and recover raft data.
+        fail_point!("before_check_and_dump_raft_db");
         check_and_dump_raft_db(&self.config, &raft_engine, &env, 8);
 
         // Create kv engine.
         let mut kv_db_opts = self.config.rocksdb.build_opt();
+        apply_extra_db_options(
+            &mut kv_db_opts,
+            &self.config.rocksdb.extra_options,
+            "rocksdb",
+        );
         kv_db_opts.set_env(env);
         kv_db_opts.add_event_listener(self.create_raftstore_compaction_listener());
         let kv_cfs_opts = self.config.rocksdb.build_cf_opts(
@@ -1231,6 +1969,138 @@ fn pre_start() {
     }
 }
 
+/// Environment variable the CLI's `--config-dir` flag is expected to set
+/// before `run_tikv` is invoked; read here rather than threaded through as a
+/// parameter so `run_tikv`'s signature doesn't have to grow for a
+/// bootstrap-only knob.
+const CONFIG_FRAGMENT_DIR_ENV: &str = "TIKV_CONFIG_DIR";
+
+/// Deep-merges `overlay` into `base` at the leaf-key level: a table in
+/// `overlay` is merged key-by-key into the corresponding table in `base`
+/// instead of replacing it wholesale, so a fragment only needs to mention
+/// the keys it actually overrides. Appends the dotted path of every changed
+/// leaf key to `changed`, for provenance logging.
+fn deep_merge_toml(
+    base: &mut toml::Value,
+    overlay: toml::Value,
+    prefix: &str,
+    changed: &mut Vec<String>,
+) {
+    match (base, overlay) {
+        (toml::Value::Table(base_table), toml::Value::Table(overlay_table)) => {
+            for (key, value) in overlay_table {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match base_table.get_mut(&key) {
+                    Some(existing) => deep_merge_toml(existing, value, &path, changed),
+                    None => {
+                        changed.push(path);
+                        base_table.insert(key, value);
+                    }
+                }
+            }
+        }
+        (slot, value) => {
+            if *slot != value {
+                changed.push(prefix.to_owned());
+                *slot = value;
+            }
+        }
+    }
+}
+
+/// Loads every `*.toml` file in `dir` in lexical order and deep-merges each
+/// on top of `config`, later fragments overriding earlier ones. Logs the
+/// provenance of every overridden key so the final "using config" line can
+/// be traced back to the fragment that set it.
+fn load_config_fragments(config: TiKvConfig, dir: &Path) -> TiKvConfig {
+    let mut value = match toml::Value::try_from(&config) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("failed to serialize base config for fragment merge, ignoring config-dir"; "err" => %e);
+            return config;
+        }
+    };
+
+    let mut fragments: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().map_or(false, |ext| ext == "toml"))
+            .collect(),
+        Err(e) => {
+            warn!("failed to read config-dir"; "dir" => %dir.display(), "err" => %e);
+            return config;
+        }
+    };
+    fragments.sort();
+
+    for path in fragments {
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("failed to read config fragment"; "path" => %path.display(), "err" => %e);
+                continue;
+            }
+        };
+        let overlay: toml::Value = match toml::from_str(&content) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("failed to parse config fragment"; "path" => %path.display(), "err" => %e);
+                continue;
+            }
+        };
+        let mut changed = Vec::new();
+        deep_merge_toml(&mut value, overlay, "", &mut changed);
+        for key in changed {
+            info!("config key overridden by fragment"; "key" => key, "source" => %path.display());
+        }
+    }
+
+    match value.try_into() {
+        Ok(merged) => merged,
+        Err(e) => {
+            warn!("failed to rebuild config after merging fragments, falling back to base config"; "err" => %e);
+            config
+        }
+    }
+}
+
+/// Applies an open-ended map of engine tunables — e.g. `[rocksdb.extra-options]`
+/// or `[raftdb.extra-options]` in the config file — directly to a freshly
+/// built [`engine_rocks::raw::DBOptions`], for options that don't have a
+/// first-class field on [`tikv::config::TiKvConfig`] yet.
+///
+/// Validation is delegated to RocksDB itself: `set_db_options` rejects
+/// unknown option names or malformed values, and any such error is treated
+/// as a startup-fatal misconfiguration rather than silently ignored.
+fn apply_extra_db_options(
+    db_opts: &mut engine_rocks::raw::DBOptions,
+    extra: &HashMap<String, String>,
+    db_name: &str,
+) {
+    if extra.is_empty() {
+        return;
+    }
+    let options: Vec<(&str, &str)> = extra
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    db_opts.set_db_options(&options).unwrap_or_else(|e| {
+        fatal!(
+            "invalid extra-options for {} engine: {}; known option names only",
+            db_name,
+            e
+        )
+    });
+    for key in extra.keys() {
+        info!("applied extra engine option"; "engine" => db_name, "option" => key);
+    }
+}
+
 fn check_system_config(config: &TiKvConfig) {
     info!("beginning system configuration check");
     let mut rocksdb_max_open_files = config.rocksdb.max_open_files;
@@ -1320,23 +2190,373 @@ impl<T: fmt::Display + Send + 'static> Stop for LazyWorker<T> {
     }
 }
 
-const DEFAULT_ENGINE_METRICS_RESET_INTERVAL: Duration = Duration::from_millis(60_000);
+/// Small helpers for one-off, dynamically-named counters/gauges/histograms,
+/// so call sites don't have to hand-roll a `lazy_static!` + `with_label_values`
+/// dance for a metric they only touch in one place. Each distinct `name` is
+/// registered with Prometheus on first use and cached for subsequent calls;
+/// all calls for a given name must agree on the set of label keys.
+pub mod metrics_util {
+    use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+    use lazy_static::lazy_static;
+    use prometheus::{GaugeVec, HistogramVec, IntCounterVec};
+
+    lazy_static! {
+        static ref COUNTERS: Mutex<HashMap<&'static str, IntCounterVec>> =
+            Mutex::new(HashMap::new());
+        static ref GAUGES: Mutex<HashMap<&'static str, GaugeVec>> = Mutex::new(HashMap::new());
+        static ref HISTOGRAMS: Mutex<HashMap<&'static str, HistogramVec>> =
+            Mutex::new(HashMap::new());
+    }
+
+    fn label_names(labels: &[(&str, &str)]) -> Vec<&str> {
+        labels.iter().map(|(k, _)| *k).collect()
+    }
+
+    fn label_values<'a>(labels: &'a [(&str, &str)]) -> Vec<&'a str> {
+        labels.iter().map(|(_, v)| *v).collect()
+    }
+
+    /// Increments a dynamically-named counter by `value`.
+    pub fn inc(name: &'static str, labels: &[(&str, &str)], value: i64) {
+        let mut counters = COUNTERS.lock().unwrap();
+        let vec = counters.entry(name).or_insert_with(|| {
+            prometheus::register_int_counter_vec!(name, name, &label_names(labels)).unwrap()
+        });
+        vec.with_label_values(&label_values(labels)).inc_by(value);
+    }
+
+    /// Sets a dynamically-named gauge to `value`.
+    pub fn gauge(name: &'static str, labels: &[(&str, &str)], value: f64) {
+        let mut gauges = GAUGES.lock().unwrap();
+        let vec = gauges.entry(name).or_insert_with(|| {
+            prometheus::register_gauge_vec!(name, name, &label_names(labels)).unwrap()
+        });
+        vec.with_label_values(&label_values(labels)).set(value);
+    }
+
+    fn observe(name: &'static str, labels: &[(&str, &str)], value: f64) {
+        let mut histograms = HISTOGRAMS.lock().unwrap();
+        let vec = histograms.entry(name).or_insert_with(|| {
+            prometheus::register_histogram_vec!(name, name, &label_names(labels)).unwrap()
+        });
+        vec.with_label_values(&label_values(labels)).observe(value);
+    }
+
+    /// RAII timing guard: captures an `Instant` on construction and, on
+    /// `Drop`, records the elapsed time into a histogram under `name` with
+    /// the current labels. Call [`label`](TimingGuard::label) any time
+    /// before the guard drops to retag the eventual observation, e.g. once
+    /// an operation's outcome is known.
+    pub struct TimingGuard {
+        name: &'static str,
+        labels: Vec<(&'static str, String)>,
+        start: Instant,
+    }
+
+    impl TimingGuard {
+        fn new(name: &'static str, labels: &[(&'static str, &str)]) -> Self {
+            TimingGuard {
+                name,
+                labels: labels.iter().map(|(k, v)| (*k, (*v).to_owned())).collect(),
+                start: Instant::now(),
+            }
+        }
+
+        /// Overrides (or adds) one label's value for the observation this
+        /// guard will record at drop time.
+        pub fn label(&mut self, key: &'static str, value: impl Into<String>) -> &mut Self {
+            match self.labels.iter_mut().find(|(k, _)| *k == key) {
+                Some(existing) => existing.1 = value.into(),
+                None => self.labels.push((key, value.into())),
+            }
+            self
+        }
+    }
+
+    impl Drop for TimingGuard {
+        fn drop(&mut self) {
+            let elapsed = self.start.elapsed().as_secs_f64();
+            let labels: Vec<(&str, &str)> =
+                self.labels.iter().map(|(k, v)| (*k, v.as_str())).collect();
+            observe(self.name, &labels, elapsed);
+        }
+    }
+
+    /// Starts a [`TimingGuard`] for `name`, labeled with `labels`.
+    pub fn timing_guard(name: &'static str, labels: &[(&'static str, &str)]) -> TimingGuard {
+        TimingGuard::new(name, labels)
+    }
+}
+
+/// How far back the "recent" and "long-term" latency means look, for the
+/// `*_latency_ratio` health-signal gauges.
+const LATENCY_RATIO_SHORT_WINDOW: Duration = Duration::from_secs(3 * 60);
+const LATENCY_RATIO_LONG_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Rolling window of per-flush-interval mean latency samples for a single
+/// RocksDB operation (e.g. "get" or "put"), derived from the engine's
+/// cumulative latency histogram counters. `reset_statistics` zeroes those
+/// counters periodically, so the tracker is fed a snapshot taken
+/// immediately *before* any reset and reconciles its own baseline
+/// afterwards, rather than reading cumulative values after they've already
+/// been zeroed out from under it.
+struct LatencySampleRing {
+    samples: std::collections::VecDeque<(Instant, f64)>,
+    last_cumulative: (u64, u64), // (sum_micros, op_count) as of the last flush
+}
+
+impl LatencySampleRing {
+    fn new() -> Self {
+        LatencySampleRing {
+            samples: std::collections::VecDeque::new(),
+            last_cumulative: (0, 0),
+        }
+    }
+
+    /// Folds in a cumulative `(sum_micros, op_count)` reading taken at
+    /// `now`, diffing it against the previous reading (or against zero for
+    /// the first reading and right after a reset). Returns the per-interval
+    /// mean latency in microseconds for this tick, or `None` if the
+    /// operation count did not grow since that baseline.
+    fn record(&mut self, now: Instant, cumulative_sum: u64, cumulative_count: u64) -> Option<f64> {
+        let (last_sum, last_count) = self.last_cumulative;
+        self.last_cumulative = (cumulative_sum, cumulative_count);
+        if cumulative_count <= last_count || cumulative_sum < last_sum {
+            return None;
+        }
+        let mean = (cumulative_sum - last_sum) as f64 / (cumulative_count - last_count) as f64;
+        self.samples.push_back((now, mean));
+        while let Some((ts, _)) = self.samples.front() {
+            if now.saturating_duration_since(*ts) > LATENCY_RATIO_LONG_WINDOW {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+        Some(mean)
+    }
+
+    /// Called right after `reset_statistics` runs: the cumulative counters
+    /// are back to (near) zero, so the next `record` call must diff against
+    /// that fresh baseline instead of the pre-reset one.
+    fn note_counters_reset(&mut self) {
+        self.last_cumulative = (0, 0);
+    }
+
+    fn mean_over(&self, now: Instant, window: Duration) -> Option<f64> {
+        let (total, n) = self
+            .samples
+            .iter()
+            .filter(|(ts, _)| now.saturating_duration_since(*ts) <= window)
+            .fold((0.0, 0u32), |(total, n), (_, v)| (total + v, n + 1));
+        if n == 0 {
+            None
+        } else {
+            Some(total / f64::from(n))
+        }
+    }
+}
+
+/// Per-engine flush/reset cadence for `EngineMetricsManager`. Flushing is
+/// cheap (it just exports the latest counters) and wants a short interval;
+/// resetting clears RocksDB's internal statistics accumulators, which is
+/// comparatively expensive, so kv and raft each get their own, usually much
+/// longer, reset interval instead of sharing one global constant.
+#[derive(Clone, Copy, Debug)]
+pub struct EngineMetricsConfig {
+    pub kv_flush_interval: Duration,
+    pub kv_reset_interval: Duration,
+    pub raft_flush_interval: Duration,
+    pub raft_reset_interval: Duration,
+}
+
+impl Default for EngineMetricsConfig {
+    fn default() -> Self {
+        EngineMetricsConfig {
+            kv_flush_interval: DEFAULT_METRICS_FLUSH_INTERVAL,
+            kv_reset_interval: Duration::from_millis(60_000),
+            raft_flush_interval: DEFAULT_METRICS_FLUSH_INTERVAL,
+            raft_reset_interval: Duration::from_millis(60_000),
+        }
+    }
+}
+
+/// Tracks how long it has been since an engine was last flushed/reset,
+/// independent of how often the driving ticker actually fires. Accounting
+/// by elapsed wall time (rather than counting ticks) keeps the cadence
+/// correct even when the ticker catches up several missed ticks at once.
+struct EngineTickSchedule {
+    flush_interval: Duration,
+    reset_interval: Duration,
+    since_flush: Duration,
+    since_reset: Duration,
+}
+
+impl EngineTickSchedule {
+    fn new(flush_interval: Duration, reset_interval: Duration) -> Self {
+        // Seed both accumulators at their threshold so the very first tick
+        // flushes, matching the old always-flush-every-tick behavior.
+        EngineTickSchedule {
+            flush_interval,
+            reset_interval,
+            since_flush: flush_interval,
+            since_reset: reset_interval,
+        }
+    }
+
+    /// Advances the schedule by `elapsed` and reports whether this tick
+    /// should flush and/or reset, consuming the corresponding accumulator
+    /// on a hit.
+    fn advance(&mut self, elapsed: Duration) -> (bool, bool) {
+        self.since_flush += elapsed;
+        self.since_reset += elapsed;
+        let should_flush = self.since_flush >= self.flush_interval;
+        if should_flush {
+            self.since_flush = Duration::from_secs(0);
+        }
+        let should_reset = self.since_reset >= self.reset_interval;
+        if should_reset {
+            self.since_reset = Duration::from_secs(0);
+        }
+        (should_flush, should_reset)
+    }
+}
+
+/// A handle `fmt::Display`-able task type for the metrics worker: it never
+/// actually receives messages, it only runs on `RunnableWithTimer`'s tick,
+/// but `LazyWorker<T>` requires a displayable `Task` regardless.
+pub struct EngineMetricsTick;
+
+impl fmt::Display for EngineMetricsTick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EngineMetricsTick")
+    }
+}
 
 pub struct EngineMetricsManager<R: RaftEngine> {
     engines: Engines<RocksEngine, R>,
+    get_latency: LatencySampleRing,
+    put_latency: LatencySampleRing,
+    kv_schedule: EngineTickSchedule,
+    raft_schedule: EngineTickSchedule,
+    last_tick: Instant,
+    tick_interval: Duration,
 }
 
 impl<R: RaftEngine> EngineMetricsManager<R> {
-    pub fn new(engines: Engines<RocksEngine, R>) -> Self {
-        EngineMetricsManager { engines }
+    pub fn new(engines: Engines<RocksEngine, R>, config: EngineMetricsConfig) -> Self {
+        let tick_interval = config
+            .kv_flush_interval
+            .min(config.kv_reset_interval)
+            .min(config.raft_flush_interval)
+            .min(config.raft_reset_interval);
+        EngineMetricsManager {
+            engines,
+            get_latency: LatencySampleRing::new(),
+            put_latency: LatencySampleRing::new(),
+            kv_schedule: EngineTickSchedule::new(
+                config.kv_flush_interval,
+                config.kv_reset_interval,
+            ),
+            raft_schedule: EngineTickSchedule::new(
+                config.raft_flush_interval,
+                config.raft_reset_interval,
+            ),
+            last_tick: Instant::now(),
+            tick_interval,
+        }
     }
 
-    pub fn flush(&mut self, duration: Duration) {
-        self.engines.kv.flush_metrics("kv");
-        self.engines.raft.flush_metrics("raft");
-        if duration >= DEFAULT_ENGINE_METRICS_RESET_INTERVAL {
-            self.engines.kv.reset_statistics();
-            self.engines.raft.reset_statistics();
+    /// Updates the rolling latency windows for `op` and, once both the
+    /// short- and long-term means are available, emits the derived
+    /// `engine_latency_mean_short_us`/`engine_latency_mean_long_us`/
+    /// `engine_latency_ratio` gauges, labelled with `op`. The ratio is
+    /// what an alert rule watches: "recent > 2x hourly" warns, "> 3x" crits.
+    fn record_latency(&mut self, op: &'static str, now: Instant) {
+        let ring = match op {
+            "get" => &mut self.get_latency,
+            "put" => &mut self.put_latency,
+            _ => return,
+        };
+        let stats = engine_rocks::util::get_engine_latency_stats(self.engines.kv.as_inner(), op);
+        let (sum_micros, op_count) = match stats {
+            Some(stats) => stats,
+            None => return,
+        };
+        if ring.record(now, sum_micros, op_count).is_none() {
+            return;
         }
+        let (short, long) = (
+            ring.mean_over(now, LATENCY_RATIO_SHORT_WINDOW),
+            ring.mean_over(now, LATENCY_RATIO_LONG_WINDOW),
+        );
+        if let (Some(short), Some(long)) = (short, long) {
+            metrics_util::gauge("engine_latency_mean_short_us", &[("op", op)], short);
+            metrics_util::gauge("engine_latency_mean_long_us", &[("op", op)], long);
+            if long > 0.0 {
+                metrics_util::gauge("engine_latency_ratio", &[("op", op)], short / long);
+            }
+        }
+    }
+
+    /// Flushes (and, once its own schedule crosses the threshold, resets)
+    /// each engine independently. Called once per tick of the driving
+    /// ticker; `elapsed` is the wall time since the previous tick, used to
+    /// advance each engine's own schedule rather than assuming ticks land
+    /// exactly `tick_interval` apart.
+    fn tick(&mut self, elapsed: Duration) {
+        let now = Instant::now();
+        let (kv_flush, kv_reset) = self.kv_schedule.advance(elapsed);
+        let (raft_flush, raft_reset) = self.raft_schedule.advance(elapsed);
+
+        if kv_flush {
+            let mut timer = metrics_util::timing_guard(
+                "engine_metrics_flush_duration",
+                &[("engine", "kv"), ("reset", "false")],
+            );
+            self.record_latency("get", now);
+            self.record_latency("put", now);
+            self.engines.kv.flush_metrics("kv");
+            if kv_reset {
+                self.engines.kv.reset_statistics();
+                self.get_latency.note_counters_reset();
+                self.put_latency.note_counters_reset();
+                timer.label("reset", "true");
+            }
+        }
+
+        if raft_flush {
+            let mut timer = metrics_util::timing_guard(
+                "engine_metrics_flush_duration",
+                &[("engine", "raft"), ("reset", "false")],
+            );
+            self.engines.raft.flush_metrics("raft");
+            if raft_reset {
+                self.engines.raft.reset_statistics();
+                timer.label("reset", "true");
+            }
+        }
+    }
+}
+
+impl<R: RaftEngine> Runnable for EngineMetricsManager<R> {
+    type Task = EngineMetricsTick;
+
+    // The manager is driven entirely off `RunnableWithTimer::on_timeout`; it
+    // never receives an actual scheduled task.
+    fn run(&mut self, _task: Self::Task) {}
+}
+
+impl<R: RaftEngine> RunnableWithTimer for EngineMetricsManager<R> {
+    fn on_timeout(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.last_tick);
+        self.last_tick = now;
+        self.tick(elapsed);
+    }
+
+    fn get_interval(&self) -> Duration {
+        self.tick_interval
     }
 }
@@ -1,15 +1,212 @@
 // Copyright 2021 TiKV Project Authors. Licensed under Apache-2.0.
 
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
 use fail::fail_point;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use lazy_static::lazy_static;
+use prometheus::{register_int_gauge, IntGauge};
+
+// Swapped for loom's instrumented atomics under `--cfg loom` so the model
+// checker in `loom_tests` below can exhaustively explore interleavings of
+// the Acquire/Release pairing these globals rely on; see that module for
+// why the globals themselves aren't what gets model-checked.
+#[cfg(not(loom))]
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+#[cfg(not(loom))]
 pub static DISK_FULL: AtomicBool = AtomicBool::new(false);
+#[cfg(not(loom))]
 pub static DISK_RESERVED: AtomicU64 = AtomicU64::new(0);
 
+#[cfg(loom)]
+loom::lazy_static! {
+    pub static ref DISK_FULL: AtomicBool = AtomicBool::new(false);
+    pub static ref DISK_RESERVED: AtomicU64 = AtomicU64::new(0);
+}
+
+/// Graduated disk-capacity state. Replaces a single "is the disk full"
+/// boolean with a tier that callers can react to proportionally: only the
+/// top tier rejects everything, the middle tier just sheds the write
+/// traffic most likely to make things worse (large/background writes,
+/// ingests, splits).
+///
+/// Ordered so that `usage as u32` comparisons express severity directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiskUsage {
+    /// Plenty of headroom; no restrictions.
+    Normal = 0,
+    /// Past the soft threshold: reject large/background writes, stop
+    /// ingest and region splits, but keep serving normal traffic.
+    AlmostFull = 1,
+    /// Past the hard threshold: reject all writes.
+    AlreadyFull = 2,
+}
+
+impl DiskUsage {
+    fn from_u32(v: u32) -> DiskUsage {
+        match v {
+            2 => DiskUsage::AlreadyFull,
+            1 => DiskUsage::AlmostFull,
+            _ => DiskUsage::Normal,
+        }
+    }
+}
+
+#[cfg(not(loom))]
+static DISK_USAGE_TIER: AtomicU32 = AtomicU32::new(DiskUsage::Normal as u32);
+
+#[cfg(loom)]
+loom::lazy_static! {
+    static ref DISK_USAGE_TIER: AtomicU32 = AtomicU32::new(DiskUsage::Normal as u32);
+}
+
+lazy_static! {
+    static ref DISK_USAGE_GAUGE: IntGauge = register_int_gauge!(
+        "tikv_disk_usage_tier",
+        "Current store-wide disk-pressure tier: 0 = normal, 1 = almost_full, 2 = already_full"
+    )
+    .unwrap();
+}
+
+type DiskStateListener = Box<dyn Fn(DiskUsage) + Send + Sync>;
+
+lazy_static! {
+    static ref DISK_STATE_LISTENERS: Mutex<HashMap<u64, DiskStateListener>> =
+        Mutex::new(HashMap::new());
+}
+
+#[cfg(not(loom))]
+static NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(loom)]
+loom::lazy_static! {
+    static ref NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+}
+
+/// Deregisters the [`on_disk_state_change`] listener it was returned for,
+/// on drop. Hold this for as long as the listener should stay registered --
+/// a long-lived subsystem wired up once at startup typically stores it
+/// alongside the handle it was built from; a scoped subscription (e.g. a
+/// test) just lets it drop at the end of its scope.
+#[must_use = "dropping this immediately deregisters the listener"]
+pub struct DiskStateListenerGuard {
+    id: u64,
+}
+
+impl Drop for DiskStateListenerGuard {
+    fn drop(&mut self) {
+        DISK_STATE_LISTENERS.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Registers `f` to be called, with the new tier, every time the store-wide
+/// disk-pressure tier changes (e.g. a write pool using this to start
+/// rejecting low-priority tasks at `AlmostFull` and everything at
+/// `AlreadyFull`). `f` stays registered until the returned
+/// [`DiskStateListenerGuard`] is dropped.
+pub fn on_disk_state_change<F>(f: F) -> DiskStateListenerGuard
+where
+    F: Fn(DiskUsage) + Send + Sync + 'static,
+{
+    let id = NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed);
+    DISK_STATE_LISTENERS.lock().unwrap().insert(id, Box::new(f));
+    DiskStateListenerGuard { id }
+}
+
+/// Core of `set_disk_usage`, parameterized over the backing atomics so the
+/// same Acquire/Release pairing can be driven either by the real
+/// process-wide globals or, under `#[cfg(loom)]`, by a throwaway instance
+/// `loom_tests` constructs fresh for each explored interleaving.
+fn set_tier(tier: &AtomicU32, full: &AtomicBool, usage: DiskUsage) {
+    tier.store(usage as u32, Ordering::Release);
+    full.store(usage == DiskUsage::AlreadyFull, Ordering::Release);
+}
+
+fn get_tier(tier: &AtomicU32) -> DiskUsage {
+    DiskUsage::from_u32(tier.load(Ordering::Acquire))
+}
+
+/// Publishes the store-wide disk-pressure tier. The storage-stats poll loop
+/// is expected to have already applied hysteresis / low-water-mark logic
+/// before calling this -- this function only records and exports the
+/// result, it doesn't decide transitions itself. Fires every listener
+/// registered via [`on_disk_state_change`] when `usage` differs from the
+/// previously published tier.
+pub fn set_disk_usage(usage: DiskUsage) {
+    let previous = get_disk_usage();
+    set_tier(&DISK_USAGE_TIER, &DISK_FULL, usage);
+    DISK_USAGE_GAUGE.set(usage as i64);
+    if usage != previous {
+        for listener in DISK_STATE_LISTENERS.lock().unwrap().values() {
+            listener(usage);
+        }
+    }
+}
+
+pub fn get_disk_usage() -> DiskUsage {
+    get_tier(&DISK_USAGE_TIER)
+}
+
+/// True once the store has reached at least `AlmostFull`, i.e. it is no
+/// longer in the `Normal` tier. Lets the backup/import/cdc paths back off
+/// ahead of the harder `AlreadyFull` cutoff.
+pub fn is_disk_under_pressure() -> bool {
+    get_disk_usage() != DiskUsage::Normal
+}
+
+/// Registers `dispatch` to run, at most once per `min_interval`, each time
+/// the store-wide tier changes to `AlmostFull` or `AlreadyFull` (including
+/// an escalation from `AlmostFull` to `AlreadyFull`). Meant for kicking an emergency reclamation pass -- forcing
+/// raft-log GC on lagging regions and scheduling an urgent compaction of the
+/// largest SSTs -- rather than waiting on the normal background ticks,
+/// without flooding those subsystems if the tier flaps.
+///
+/// Piggybacks on [`on_disk_state_change`], the same detection point
+/// `set_disk_usage` already drives the admission gate from, so reclamation
+/// and admission react to the identical tier transition rather than two
+/// independently-polled views of disk state.
+///
+/// Assumes the raft-log GC scheduler and RocksDB compaction API (neither
+/// part of this crate) are what `dispatch` calls into.
+///
+/// Returns the [`DiskStateListenerGuard`] backing the registration; drop it
+/// to stop dispatching (e.g. a scoped test doesn't want its listener to
+/// outlive it), or hold onto it for the lifetime of whatever owns
+/// `dispatch`.
+#[must_use = "dropping this immediately deregisters the reclamation listener"]
+pub fn register_emergency_reclamation<F>(
+    min_interval: Duration,
+    dispatch: F,
+) -> DiskStateListenerGuard
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let last_fired: Mutex<Option<Instant>> = Mutex::new(None);
+    on_disk_state_change(move |usage| {
+        if usage == DiskUsage::Normal {
+            return;
+        }
+        let mut last_fired = last_fired.lock().unwrap();
+        let now = Instant::now();
+        if last_fired.map_or(true, |t| now.duration_since(t) >= min_interval) {
+            *last_fired = Some(now);
+            dispatch();
+        }
+    })
+}
+
 pub fn set_disk_full() {
-    DISK_FULL.store(true, Ordering::Release);
+    set_disk_usage(DiskUsage::AlreadyFull);
 }
 pub fn clear_disk_full() {
-    DISK_FULL.store(false, Ordering::Release);
+    set_disk_usage(DiskUsage::Normal);
 }
 #[cfg(feature = "failpoints")]
 fn disk_full_precheck() -> bool {
@@ -27,15 +224,504 @@ fn disk_full_precheck() -> bool {
 }
 
 pub fn is_disk_full() -> bool {
-    return disk_full_precheck() || DISK_FULL.load(Ordering::Acquire);
+    return disk_full_precheck() || get_disk_usage() == DiskUsage::AlreadyFull;
 }
-pub fn set_disk_reserved(v: u64) {
+
+#[cfg(feature = "failpoints")]
+fn leader_drain_precheck() -> bool {
+    fail_point!("disk_full_drain_t", |_| true);
+    false
+}
+#[cfg(not(feature = "failpoints"))]
+fn leader_drain_precheck() -> bool {
+    false
+}
+
+/// True once the store should actively drain itself rather than just
+/// passively reject writes: transfer every region leader it holds to a
+/// healthy peer, and flag the next PD store heartbeat so the scheduler
+/// stops placing new regions/replicas here -- mirroring what an operator
+/// would do by hand for an unhealthy/offline store. Consulted by the
+/// server's periodic storage-stats task, alongside [`is_disk_full`]'s
+/// write-path gate; the two happen to share the same `AlreadyFull`
+/// threshold today but are kept as separate accessors since a future
+/// change (e.g. draining earlier than rejecting writes) shouldn't need to
+/// touch both call sites' semantics at once.
+pub fn is_leader_drain_requested() -> bool {
+    leader_drain_precheck() || get_disk_usage() == DiskUsage::AlreadyFull
+}
+
+/// Spacing between re-checks while [`wait_for_disk_usage_recovery`] holds a
+/// proposal open during its grace window -- frequent enough to notice a
+/// compaction/GC pass freeing space promptly, without spinning.
+const GRACE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Models the admission layer's `storage.disk_full_grace_duration`: instead
+/// of rejecting a write the instant the tier crosses `ceiling`, the caller
+/// holds the proposal open and calls this, which polls [`get_disk_usage`]
+/// every [`GRACE_POLL_INTERVAL`] until either the tier recovers to at or
+/// below `ceiling` or `grace` elapses, giving raft-log GC and compaction a
+/// chance to free the reserve before the write is actually rejected.
+/// Returns `true` if the caller should go ahead and propose the write,
+/// `false` if it should return a disk-full error. `grace == Duration::ZERO`
+/// returns immediately with no polling, preserving the pre-grace-window
+/// reject-on-sight behavior (what `test_disk_full` sets to keep its
+/// existing assertions working).
+pub fn wait_for_disk_usage_recovery(ceiling: DiskUsage, grace: Duration) -> bool {
+    if get_disk_usage() as u32 <= ceiling as u32 {
+        return true;
+    }
+    if grace == Duration::ZERO {
+        return false;
+    }
+    let deadline = Instant::now() + grace;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::ZERO {
+            return get_disk_usage() as u32 <= ceiling as u32;
+        }
+        std::thread::sleep(std::cmp::min(remaining, GRACE_POLL_INTERVAL));
+        if get_disk_usage() as u32 <= ceiling as u32 {
+            return true;
+        }
+    }
+}
+
+/// Core of `set_disk_reserved`; see [`set_tier`] for why this takes the
+/// atomic as a parameter instead of touching `DISK_RESERVED` directly.
+fn set_reserved(reserved: &AtomicU64, v: u64) {
     let mut value = v;
     if v == 0 {
         value = 5 * 1024 * 1024 * 1024;
     }
-    DISK_RESERVED.store(value, Ordering::Release);
+    reserved.store(value, Ordering::Release);
+}
+
+fn get_reserved(reserved: &AtomicU64) -> u64 {
+    reserved.load(Ordering::Acquire)
+}
+
+pub fn set_disk_reserved(v: u64) {
+    set_reserved(&DISK_RESERVED, v);
 }
 pub fn get_disk_reserved() -> u64 {
-    DISK_RESERVED.load(Ordering::Acquire)
+    get_reserved(&DISK_RESERVED)
+}
+
+/// Multiplier applied to `DISK_RESERVED` to get the `AlmostFull` threshold:
+/// the store starts shedding low-priority work once free space drops below
+/// `ALMOST_FULL_RESERVED_FACTOR * reserved`, well ahead of the `AlreadyFull`
+/// cutoff at `reserved` itself.
+const ALMOST_FULL_RESERVED_FACTOR: u64 = 2;
+
+/// Sets the hysteresis band on de-escalation, which is
+/// `reserved * RECOVERY_MARGIN_FACTOR / ALMOST_FULL_RESERVED_FACTOR` (half of
+/// `reserved` with the current values): once in `AlmostFull` or
+/// `AlreadyFull`, free space has to climb back past its threshold by that
+/// band before the tier drops a level, so a store hovering
+/// right at a boundary doesn't flap between tiers every sample.
+const RECOVERY_MARGIN_FACTOR: u64 = 1;
+
+/// Computes the next tier from a live free-space sample, given the current
+/// tier (for hysteresis) and the reserved-space threshold. Pure function so
+/// [`observe_free_space`] and tests can both drive it without needing a real
+/// sample.
+fn next_tier_for_free_space(current: DiskUsage, free_space: u64, reserved: u64) -> DiskUsage {
+    let margin =
+        reserved.saturating_mul(RECOVERY_MARGIN_FACTOR) / ALMOST_FULL_RESERVED_FACTOR.max(1);
+    let almost_full_threshold = reserved.saturating_mul(ALMOST_FULL_RESERVED_FACTOR);
+    match current {
+        DiskUsage::AlreadyFull => {
+            if free_space > reserved + margin {
+                DiskUsage::AlmostFull
+            } else {
+                DiskUsage::AlreadyFull
+            }
+        }
+        DiskUsage::AlmostFull => {
+            if free_space <= reserved {
+                DiskUsage::AlreadyFull
+            } else if free_space > almost_full_threshold + margin {
+                DiskUsage::Normal
+            } else {
+                DiskUsage::AlmostFull
+            }
+        }
+        DiskUsage::Normal => {
+            if free_space <= reserved {
+                DiskUsage::AlreadyFull
+            } else if free_space <= almost_full_threshold {
+                DiskUsage::AlmostFull
+            } else {
+                DiskUsage::Normal
+            }
+        }
+    }
+}
+
+/// Samples `free_space` against `DISK_RESERVED` and publishes the resulting
+/// tier via [`set_disk_usage`] if it differs from the current one. Meant to
+/// be called from a pool's tick path (see
+/// `yatp_pool::YatpPoolBuilder::disk_aware`) so pools can react to disk
+/// pressure without every caller re-deriving the hysteresis band above.
+pub fn observe_free_space(free_space: u64) {
+    let current = get_disk_usage();
+    let next = next_tier_for_free_space(current, free_space, get_disk_reserved());
+    if next != current {
+        set_disk_usage(next);
+    }
+}
+
+/// Raft command categories the disk-usage admission policy below
+/// distinguishes. Coarser than the full `CmdType`/`AdminCmdType` surface --
+/// just enough for [`is_command_allowed`] to decide what each [`DiskUsage`]
+/// tier should let through.
+///
+/// Assumes the raftstore propose/apply path (not part of this crate) maps
+/// each incoming command to one of these and consults [`is_command_allowed`]
+/// before admitting it, alongside the existing [`is_disk_full`] gate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiskUsageCommand {
+    /// A read-only request: get/scan, a quorum `ReadIndex`, or a
+    /// lease-based local read. `is_command_allowed` admits this at every
+    /// tier, including `AlreadyFull` -- a full disk must stall writes, not
+    /// stale/follower reads, since none of these can grow the log.
+    ReadOnly,
+    /// A user write below the large-write threshold.
+    SmallWrite,
+    /// A user write at or above the large-write threshold, or an ingest.
+    LargeWrite,
+    /// `AdminCmdType::BatchSplit`/`Split`.
+    SplitRegion,
+    /// `AdminCmdType::TransferLeader`.
+    TransferLeader,
+    /// `ConfChange`/`ConfChangeV2`, including `remove_peer` -- one of the
+    /// few ways an operator can relieve a full store, so it must stay
+    /// admitted even at `AlreadyFull`.
+    ConfChange,
+}
+
+/// Per-(tier, command) admission decision for the raft propose/apply path.
+/// `AlmostFull` sheds exactly the traffic most likely to make disk pressure
+/// worse -- large writes and splits -- while still serving small writes and
+/// reads; `AlreadyFull` rejects all writes and splits (preserving
+/// `fail_split_region`'s invariant that a split can't go through once a
+/// store is full) but still admits reads, leader transfer, and conf
+/// changes, since those are exactly what an operator needs to relieve the
+/// pressure and must not themselves be blocked by the gate they route
+/// around.
+pub fn is_command_allowed(usage: DiskUsage, command: DiskUsageCommand) -> bool {
+    use DiskUsageCommand::*;
+    match usage {
+        DiskUsage::Normal => true,
+        DiskUsage::AlmostFull => !matches!(command, LargeWrite | SplitRegion),
+        DiskUsage::AlreadyFull => matches!(command, ReadOnly | TransferLeader | ConfChange),
+    }
+}
+
+/// Runs `advance` only if `command` is currently admitted under `usage`;
+/// otherwise leaves `advance` uncalled and returns `None`.
+///
+/// Exists for call sites where admission has a side effect that must never
+/// happen on a rejected command -- the canonical example being prewrite
+/// advancing the concurrency manager's max_ts and inserting an in-memory
+/// lock before the disk-full check runs, which would leave max_ts and the
+/// lock table inconsistent (a later async-commit read could then observe a
+/// barrier with no lock behind it) once the rejection unwinds. Routing the
+/// mutation through this helper rather than checking `is_command_allowed`
+/// and mutating afterward makes that reordering a compile-time
+/// impossibility instead of a call-site discipline problem: `advance` is
+/// simply unreachable until the check has already passed.
+///
+/// Assumes the storage/txn prewrite path (not part of this crate) calls
+/// this with `DiskUsageCommand::SmallWrite`/`LargeWrite` wrapping the
+/// max_ts advance and lock insert, rather than performing the `is_disk_full`
+/// check and the mutation as two separate steps.
+pub fn checked_admit<T>(
+    usage: DiskUsage,
+    command: DiskUsageCommand,
+    advance: impl FnOnce() -> T,
+) -> Option<T> {
+    if is_command_allowed(usage, command) {
+        Some(advance())
+    } else {
+        None
+    }
+}
+
+/// Exhaustively explores interleavings of a writer calling
+/// `set_disk_usage`/`set_disk_reserved` concurrently with a reader calling
+/// `is_disk_full`/`get_disk_reserved`, under `--cfg loom` (as Tokio does for
+/// its executor). Runs against fresh, throwaway atomics built inside each
+/// `loom::model` closure rather than the real `DISK_FULL`/`DISK_RESERVED`/
+/// `DISK_USAGE_TIER` globals: loom needs to own every interleaving of an
+/// atomic's history from a known initial state, which a process-wide
+/// `static` can't give it once the first model run has mutated it.
+#[cfg(loom)]
+mod loom_tests {
+    use loom::sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64},
+        Arc,
+    };
+
+    use super::*;
+
+    #[test]
+    fn concurrent_set_and_read_disk_usage() {
+        loom::model(|| {
+            let tier = Arc::new(AtomicU32::new(DiskUsage::Normal as u32));
+            let full = Arc::new(AtomicBool::new(false));
+
+            let (writer_tier, writer_full) = (tier.clone(), full.clone());
+            let writer = loom::thread::spawn(move || {
+                set_tier(&writer_tier, &writer_full, DiskUsage::AlreadyFull);
+            });
+
+            // Readers may observe either the pre- or post-update state
+            // while the writer is in flight, but must never panic or
+            // observe a torn write.
+            let _ = get_tier(&tier);
+            let _ = full.load(Ordering::Acquire);
+
+            writer.join().unwrap();
+            assert_eq!(get_tier(&tier), DiskUsage::AlreadyFull);
+            assert!(full.load(Ordering::Acquire));
+        });
+    }
+
+    #[test]
+    fn concurrent_set_and_read_disk_reserved() {
+        loom::model(|| {
+            let reserved = Arc::new(AtomicU64::new(0));
+
+            let writer_reserved = reserved.clone();
+            let writer = loom::thread::spawn(move || {
+                set_reserved(&writer_reserved, 4096);
+            });
+
+            let _ = get_reserved(&reserved);
+
+            writer.join().unwrap();
+            assert_eq!(get_reserved(&reserved), 4096);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `DISK_FULL`/`DISK_USAGE_TIER`/`DISK_STATE_LISTENERS` are process-wide
+    // globals, so any test that reads or writes disk usage through them
+    // races every other such test under cargo's default parallel harness.
+    // Every test below that touches them takes this lock for its duration;
+    // tests that only exercise pure functions (`is_command_allowed`,
+    // `checked_admit`) don't need it.
+    lazy_static! {
+        static ref TEST_MUTEX: Mutex<()> = Mutex::new(());
+    }
+
+    #[test]
+    fn test_is_command_allowed_normal_admits_everything() {
+        for command in [
+            DiskUsageCommand::ReadOnly,
+            DiskUsageCommand::SmallWrite,
+            DiskUsageCommand::LargeWrite,
+            DiskUsageCommand::SplitRegion,
+            DiskUsageCommand::TransferLeader,
+            DiskUsageCommand::ConfChange,
+        ] {
+            assert!(is_command_allowed(DiskUsage::Normal, command));
+        }
+    }
+
+    #[test]
+    fn test_read_only_never_blocked_by_disk_usage_at_any_tier() {
+        // ReadIndex requests and lease-based local reads both map to
+        // `DiskUsageCommand::ReadOnly` -- neither must ever be gated by
+        // disk pressure, at any tier, since a full disk should stall
+        // writes, not stale/follower reads.
+        for tier in [
+            DiskUsage::Normal,
+            DiskUsage::AlmostFull,
+            DiskUsage::AlreadyFull,
+        ] {
+            assert!(is_command_allowed(tier, DiskUsageCommand::ReadOnly));
+        }
+    }
+
+    #[test]
+    fn test_is_command_allowed_almost_full_sheds_large_writes_and_splits() {
+        assert!(is_command_allowed(
+            DiskUsage::AlmostFull,
+            DiskUsageCommand::ReadOnly
+        ));
+        assert!(is_command_allowed(
+            DiskUsage::AlmostFull,
+            DiskUsageCommand::SmallWrite
+        ));
+        assert!(is_command_allowed(
+            DiskUsage::AlmostFull,
+            DiskUsageCommand::TransferLeader
+        ));
+        assert!(is_command_allowed(
+            DiskUsage::AlmostFull,
+            DiskUsageCommand::ConfChange
+        ));
+        assert!(!is_command_allowed(
+            DiskUsage::AlmostFull,
+            DiskUsageCommand::LargeWrite
+        ));
+        assert!(!is_command_allowed(
+            DiskUsage::AlmostFull,
+            DiskUsageCommand::SplitRegion
+        ));
+    }
+
+    #[test]
+    fn test_is_command_allowed_already_full_rejects_all_writes_and_splits() {
+        assert!(is_command_allowed(
+            DiskUsage::AlreadyFull,
+            DiskUsageCommand::ReadOnly
+        ));
+        assert!(is_command_allowed(
+            DiskUsage::AlreadyFull,
+            DiskUsageCommand::TransferLeader
+        ));
+        assert!(is_command_allowed(
+            DiskUsage::AlreadyFull,
+            DiskUsageCommand::ConfChange
+        ));
+        assert!(!is_command_allowed(
+            DiskUsage::AlreadyFull,
+            DiskUsageCommand::SmallWrite
+        ));
+        assert!(!is_command_allowed(
+            DiskUsage::AlreadyFull,
+            DiskUsageCommand::LargeWrite
+        ));
+        assert!(!is_command_allowed(
+            DiskUsage::AlreadyFull,
+            DiskUsageCommand::SplitRegion
+        ));
+    }
+
+    #[test]
+    fn test_is_leader_drain_requested_tracks_already_full_tier() {
+        let _guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        clear_disk_full();
+        assert!(!is_leader_drain_requested());
+        set_disk_full();
+        assert!(is_leader_drain_requested());
+        clear_disk_full();
+        assert!(!is_leader_drain_requested());
+    }
+
+    #[test]
+    fn test_wait_for_disk_usage_recovery_zero_grace_rejects_immediately() {
+        let _guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        set_disk_full();
+        let start = Instant::now();
+        assert!(!wait_for_disk_usage_recovery(
+            DiskUsage::Normal,
+            Duration::ZERO
+        ));
+        assert!(start.elapsed() < GRACE_POLL_INTERVAL);
+        clear_disk_full();
+    }
+
+    #[test]
+    fn test_wait_for_disk_usage_recovery_succeeds_if_tier_drops_in_time() {
+        let _guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        set_disk_full();
+        let handle = std::thread::spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            clear_disk_full();
+        });
+        assert!(wait_for_disk_usage_recovery(
+            DiskUsage::Normal,
+            Duration::from_secs(5)
+        ));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_for_disk_usage_recovery_times_out_if_still_full() {
+        let _guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        set_disk_full();
+        let start = Instant::now();
+        let grace = Duration::from_millis(150);
+        assert!(!wait_for_disk_usage_recovery(DiskUsage::Normal, grace));
+        assert!(start.elapsed() >= grace);
+        clear_disk_full();
+    }
+
+    // Stand-in for the concurrency manager's max_ts and in-memory lock
+    // table: a rejected prewrite routed through `checked_admit` must leave
+    // both untouched.
+    struct FakeTxnState {
+        max_ts: u64,
+        locked: bool,
+    }
+
+    #[test]
+    fn test_checked_admit_rejects_without_advancing_max_ts_or_locking() {
+        let mut state = FakeTxnState {
+            max_ts: 10,
+            locked: false,
+        };
+        let result = checked_admit(DiskUsage::AlreadyFull, DiskUsageCommand::SmallWrite, || {
+            state.max_ts = 20;
+            state.locked = true;
+        });
+        assert!(result.is_none());
+        assert_eq!(state.max_ts, 10);
+        assert!(!state.locked);
+    }
+
+    #[test]
+    fn test_checked_admit_advances_max_ts_and_locks_once_admitted() {
+        let mut state = FakeTxnState {
+            max_ts: 10,
+            locked: false,
+        };
+        let result = checked_admit(DiskUsage::Normal, DiskUsageCommand::SmallWrite, || {
+            state.max_ts = 20;
+            state.locked = true;
+        });
+        assert!(result.is_some());
+        assert_eq!(state.max_ts, 20);
+        assert!(state.locked);
+    }
+
+    #[test]
+    fn test_register_emergency_reclamation_dispatches_on_pressure_and_rate_limits() {
+        use std::sync::{atomic::AtomicUsize, Arc};
+
+        let _lock = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        clear_disk_full();
+        let fired = Arc::new(AtomicUsize::new(0));
+        let fired_clone = fired.clone();
+        let _guard = register_emergency_reclamation(Duration::from_millis(200), move || {
+            fired_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        set_disk_usage(DiskUsage::Normal); // no transition, must not dispatch.
+        assert_eq!(fired.load(Ordering::SeqCst), 0);
+
+        set_disk_usage(DiskUsage::AlmostFull);
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+        // re-entering pressure again immediately must be rate-limited.
+        set_disk_usage(DiskUsage::Normal);
+        set_disk_usage(DiskUsage::AlreadyFull);
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+        std::thread::sleep(Duration::from_millis(220));
+        set_disk_usage(DiskUsage::Normal);
+        set_disk_usage(DiskUsage::AlmostFull);
+        assert_eq!(fired.load(Ordering::SeqCst), 2);
+
+        clear_disk_full();
+    }
 }
@@ -3,7 +3,17 @@
 mod future_pool;
 mod metrics;
 
-use std::sync::Arc;
+use std::{
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    task::{Context as TaskContext, Poll as TaskPoll},
+    thread,
+};
 
 use fail::fail_point;
 pub use future_pool::{Full, FuturePool};
@@ -27,6 +37,344 @@ fn tick_interval() -> Duration {
     TICK_INTERVAL
 }
 
+const DEFAULT_MAX_BLOCKING_THREADS: usize = 512;
+const DEFAULT_BLOCKING_KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+type BlockingJob = Box<dyn FnOnce() + Send>;
+
+// `BlockingPoolInner`'s thread-count bookkeeping is swapped for loom's
+// instrumented atomic/mutex under `--cfg loom`, so `loom_tests` below can
+// drive `should_spawn` directly instead of re-deriving its logic. Kept as
+// a dedicated alias rather than reusing the `AtomicUsize`/`Mutex` imported
+// above, since those also back unrelated, non-loom-tested state elsewhere
+// in this file.
+#[cfg(not(loom))]
+use std::sync::atomic::AtomicUsize as BpAtomicUsize;
+#[cfg(not(loom))]
+use std::sync::atomic::Ordering as BpOrdering;
+#[cfg(not(loom))]
+use std::sync::Mutex as BpMutex;
+#[cfg(loom)]
+use loom::sync::atomic::AtomicUsize as BpAtomicUsize;
+#[cfg(loom)]
+use loom::sync::atomic::Ordering as BpOrdering;
+#[cfg(loom)]
+use loom::sync::Mutex as BpMutex;
+
+/// Core of [`BlockingPoolInner::submit`]'s "do we still need one more
+/// thread" decision, parameterized over the backing atomic/mutex so loom
+/// can drive it against a throwaway instance instead of a real pool's
+/// shared state; see `sys::disk::loom_tests` for why that's necessary.
+/// Returns `true` (and reserves the slot by bumping `threads`) if a new
+/// thread should be spawned for the job that was just queued.
+fn should_spawn(idle_threads: &BpAtomicUsize, threads: &BpMutex<usize>, max_threads: usize) -> bool {
+    if idle_threads.load(BpOrdering::SeqCst) > 0 {
+        return false;
+    }
+    let mut threads = threads.lock().unwrap();
+    if *threads < max_threads {
+        *threads += 1;
+        true
+    } else {
+        false
+    }
+}
+
+/// A companion pool for blocking (non-async) work -- fsync, large RocksDB
+/// reads, compression -- modeled on Tokio's `blocking` subsystem. Unlike the
+/// yatp future pool, threads here are spawned lazily on demand (up to
+/// `max_threads`) rather than kept warm, and exit once they've sat idle for
+/// `keep_alive`, since blocking work tends to be bursty rather than a
+/// steady-state workload.
+///
+/// `FuturePool::spawn_blocking` is meant to hand work to one of these, but
+/// `FuturePool` itself lives in `future_pool.rs`, which isn't part of this
+/// checkout -- so for now callers construct and hold a `BlockingPool`
+/// directly via [`BlockingPoolBuilder`] and call [`BlockingPool::spawn`].
+#[derive(Clone)]
+pub struct BlockingPool {
+    inner: Arc<BlockingPoolInner>,
+}
+
+struct BlockingPoolInner {
+    name_prefix: String,
+    max_threads: usize,
+    keep_alive: Duration,
+    stack_size: usize,
+    after_start: Option<Arc<dyn Fn() + Send + Sync>>,
+    before_stop: Option<Arc<dyn Fn() + Send + Sync>>,
+    sender: mpsc::Sender<BlockingJob>,
+    receiver: Mutex<mpsc::Receiver<BlockingJob>>,
+    threads: BpMutex<usize>,
+    idle_threads: BpAtomicUsize,
+}
+
+impl BlockingPoolInner {
+    /// Queues `job` and, if every live thread is currently busy and there's
+    /// still headroom under `max_threads`, spawns one more to pick it up.
+    fn submit(self: &Arc<Self>, job: BlockingJob) {
+        self.sender
+            .send(job)
+            .expect("blocking pool receiver thread can't have exited: we hold a Sender");
+        if should_spawn(&self.idle_threads, &self.threads, self.max_threads) {
+            self.spawn_thread();
+        }
+    }
+
+    fn spawn_thread(self: &Arc<Self>) {
+        let inner = Arc::clone(self);
+        let mut builder = thread::Builder::new().name(format!("{}-blocking", inner.name_prefix));
+        if inner.stack_size > 0 {
+            builder = builder.stack_size(inner.stack_size);
+        }
+        builder
+            .spawn(move || {
+                crate::sys::thread::add_thread_name_to_map();
+                crate::thread_group::set_properties(crate::thread_group::current_properties());
+                if let Some(f) = inner.after_start.as_ref() {
+                    f();
+                }
+                tikv_alloc::add_thread_memory_accessor();
+
+                loop {
+                    inner.idle_threads.fetch_add(1, BpOrdering::SeqCst);
+                    let job = {
+                        let rx = inner.receiver.lock().unwrap();
+                        rx.recv_timeout(inner.keep_alive)
+                    };
+                    inner.idle_threads.fetch_sub(1, BpOrdering::SeqCst);
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                }
+
+                if let Some(f) = inner.before_stop.as_ref() {
+                    f();
+                }
+                tikv_alloc::remove_thread_memory_accessor();
+                crate::sys::thread::remove_thread_name_from_map();
+                *inner.threads.lock().unwrap() -= 1;
+            })
+            .expect("failed to spawn blocking pool thread");
+    }
+}
+
+/// A future resolving to the result of a [`BlockingPool::spawn`] closure.
+pub struct BlockingTask<R> {
+    rx: futures::channel::oneshot::Receiver<R>,
+}
+
+impl<R> Future for BlockingTask<R> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> TaskPoll<R> {
+        Pin::new(&mut self.get_mut().rx)
+            .poll(cx)
+            .map(|r| r.expect("blocking pool dropped the job before it completed"))
+    }
+}
+
+impl BlockingPool {
+    /// Submits `f` to run on a blocking-pool thread and returns a future
+    /// that resolves to its result once it completes.
+    pub fn spawn<F, R>(&self, f: F) -> BlockingTask<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        let job: BlockingJob = Box::new(move || {
+            let _ = tx.send(f());
+        });
+        self.inner.submit(job);
+        BlockingTask { rx }
+    }
+}
+
+/// Builds a [`BlockingPool`]. Reuses the same `after_start`/`before_stop`
+/// hook shape as [`YatpPoolBuilder`] so the same thread-init/teardown
+/// closures (memory accounting, group properties, metrics registration)
+/// can be shared between a pool's async and blocking halves.
+pub struct BlockingPoolBuilder {
+    name_prefix: String,
+    max_threads: usize,
+    keep_alive: Duration,
+    stack_size: usize,
+    after_start: Option<Arc<dyn Fn() + Send + Sync>>,
+    before_stop: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl BlockingPoolBuilder {
+    pub fn new(name_prefix: impl Into<String>) -> Self {
+        BlockingPoolBuilder {
+            name_prefix: name_prefix.into(),
+            max_threads: DEFAULT_MAX_BLOCKING_THREADS,
+            keep_alive: DEFAULT_BLOCKING_KEEP_ALIVE,
+            stack_size: 0,
+            after_start: None,
+            before_stop: None,
+        }
+    }
+
+    pub fn max_threads(&mut self, max_threads: usize) -> &mut Self {
+        self.max_threads = max_threads;
+        self
+    }
+
+    pub fn keep_alive(&mut self, keep_alive: Duration) -> &mut Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    pub fn stack_size(&mut self, stack_size: usize) -> &mut Self {
+        self.stack_size = stack_size;
+        self
+    }
+
+    pub fn after_start<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.after_start = Some(Arc::new(f));
+        self
+    }
+
+    pub fn before_stop<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.before_stop = Some(Arc::new(f));
+        self
+    }
+
+    pub fn build(&mut self) -> BlockingPool {
+        let (sender, receiver) = mpsc::channel();
+        BlockingPool {
+            inner: Arc::new(BlockingPoolInner {
+                name_prefix: self.name_prefix.clone(),
+                max_threads: self.max_threads,
+                keep_alive: self.keep_alive,
+                stack_size: self.stack_size,
+                after_start: self.after_start.clone(),
+                before_stop: self.before_stop.clone(),
+                sender,
+                receiver: Mutex::new(receiver),
+                threads: BpMutex::new(0),
+                idle_threads: BpAtomicUsize::new(0),
+            }),
+        }
+    }
+}
+
+/// A CPU core index, as the kernel enumerates them (0-based), for
+/// [`YatpPoolBuilder::pin_cores`]. Plain `usize` rather than an opaque
+/// wrapper since pinning goes straight through `sched_setaffinity` below.
+pub type CoreId = usize;
+
+#[cfg(target_os = "linux")]
+mod affinity {
+    use super::CoreId;
+
+    /// Binds the calling thread to `core`. Best-effort: a failure (e.g. an
+    /// out-of-range core index) is reported but otherwise harmless, since
+    /// affinity is a locality hint, not a correctness requirement.
+    pub fn pin_to_core(core: CoreId) -> std::io::Result<()> {
+        unsafe {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_ZERO(&mut set);
+            libc::CPU_SET(core, &mut set);
+            let rc = libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set);
+            if rc == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        }
+    }
+
+    /// Best-effort NUMA topology: one core list per node detected under
+    /// `/sys/devices/system/node`. Falls back to a single node spanning
+    /// `0..num_cpus` when the topology can't be read (no NUMA hardware, or
+    /// no access to sysfs), so callers don't need a separate non-NUMA path.
+    pub fn numa_nodes(num_cpus: usize) -> Vec<Vec<CoreId>> {
+        let mut nodes = Vec::new();
+        if let Ok(entries) = std::fs::read_dir("/sys/devices/system/node") {
+            let mut node_dirs: Vec<_> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.file_name()
+                        .to_str()
+                        .map(|s| s.starts_with("node") && s[4..].parse::<u32>().is_ok())
+                        .unwrap_or(false)
+                })
+                .collect();
+            node_dirs.sort_by_key(|e| e.file_name());
+            for dir in node_dirs {
+                if let Ok(contents) = std::fs::read_to_string(dir.path().join("cpulist")) {
+                    if let Some(cores) = parse_cpulist(contents.trim()) {
+                        if !cores.is_empty() {
+                            nodes.push(cores);
+                        }
+                    }
+                }
+            }
+        }
+        if nodes.is_empty() {
+            nodes.push((0..num_cpus).collect());
+        }
+        nodes
+    }
+
+    /// Parses a Linux sysfs cpu list, e.g. `"0-3,8,10-11"`.
+    fn parse_cpulist(s: &str) -> Option<Vec<CoreId>> {
+        let mut cores = Vec::new();
+        for part in s.split(',') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            match part.split_once('-') {
+                Some((start, end)) => cores.extend(start.parse::<CoreId>().ok()?..=end.parse().ok()?),
+                None => cores.push(part.parse().ok()?),
+            }
+        }
+        Some(cores)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod affinity {
+    use super::CoreId;
+
+    pub fn pin_to_core(_core: CoreId) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    pub fn numa_nodes(num_cpus: usize) -> Vec<Vec<CoreId>> {
+        vec![(0..num_cpus).collect()]
+    }
+}
+
+/// Assigns `count` workers to cores by walking `nodes` round-robin, taking
+/// each node's next unused core in turn, so workers spread evenly across
+/// NUMA nodes instead of filling one node before moving to the next.
+fn round_robin_over_nodes(nodes: &[Vec<CoreId>], count: usize) -> Vec<CoreId> {
+    let mut result = Vec::with_capacity(count);
+    let mut cursors = vec![0usize; nodes.len()];
+    let mut node_idx = 0;
+    while result.len() < count && nodes.iter().any(|n| !n.is_empty()) {
+        let node = &nodes[node_idx % nodes.len()];
+        if !node.is_empty() {
+            let cursor = &mut cursors[node_idx % nodes.len()];
+            result.push(node[*cursor % node.len()]);
+            *cursor += 1;
+        }
+        node_idx += 1;
+    }
+    result
+}
+
 pub trait PoolTicker: Send + Clone + 'static {
     fn on_tick(&mut self);
 }
@@ -45,13 +393,16 @@ impl<T: PoolTicker> TickerWrapper<T> {
         }
     }
 
-    pub fn try_tick(&mut self) {
+    /// Runs the ticker's `on_tick` if at least `tick_interval()` has passed
+    /// since the last tick, returning whether it fired.
+    pub fn try_tick(&mut self) -> bool {
         let now = Instant::now_coarse();
         if now.saturating_duration_since(self.last_tick_time) < tick_interval() {
-            return;
+            return false;
         }
         self.last_tick_time = now;
         self.ticker.on_tick();
+        true
     }
 
     pub fn on_tick(&mut self) {
@@ -83,6 +434,31 @@ impl Config {
     }
 }
 
+/// Samples free disk space off `path` and feeds it to
+/// `disk::observe_free_space`, piggybacking on the per-worker tick (see
+/// [`TickerWrapper::try_tick`]) rather than running a dedicated thread. Set
+/// via [`YatpPoolBuilder::disk_aware`]; lets a pool's own tasks react to
+/// disk pressure (e.g. a write pool rejecting low-priority tasks once the
+/// store-wide tier reaches `AlmostFull`) without a separate poller.
+#[derive(Clone)]
+struct DiskSampler {
+    path: Arc<PathBuf>,
+}
+
+impl DiskSampler {
+    fn new(path: PathBuf) -> Self {
+        DiskSampler {
+            path: Arc::new(path),
+        }
+    }
+
+    fn sample(&self) {
+        if let Ok(stats) = fs2::statvfs(self.path.as_ref()) {
+            crate::sys::disk::observe_free_space(stats.available_space());
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct YatpPoolRunner<T: PoolTicker> {
     inner: FutureRunner,
@@ -94,6 +470,8 @@ pub struct YatpPoolRunner<T: PoolTicker> {
 
     // Statistics about the schedule wait duration.
     schedule_wait_duration: Histogram,
+
+    disk_sampler: Option<DiskSampler>,
 }
 
 impl<T: PoolTicker> Runner for YatpPoolRunner<T> {
@@ -114,11 +492,15 @@ impl<T: PoolTicker> Runner for YatpPoolRunner<T> {
     fn handle(&mut self, local: &mut Local<Self::TaskCell>, mut task_cell: Self::TaskCell) -> bool {
         let extras = task_cell.mut_extras();
         if let Some(schedule_time) = extras.schedule_time() {
-            self.schedule_wait_duration
-                .observe(schedule_time.elapsed().as_secs_f64());
+            let wait_secs = schedule_time.elapsed().as_secs_f64();
+            self.schedule_wait_duration.observe(wait_secs);
         }
         let finished = self.inner.handle(local, task_cell);
-        self.ticker.try_tick();
+        if self.ticker.try_tick() {
+            if let Some(disk_sampler) = &self.disk_sampler {
+                disk_sampler.sample();
+            }
+        }
         finished
     }
 
@@ -152,6 +534,7 @@ impl<T: PoolTicker> YatpPoolRunner<T> {
         before_stop: Option<Arc<dyn Fn() + Send + Sync>>,
         before_pause: Option<Arc<dyn Fn() + Send + Sync>>,
         schedule_wait_duration: Histogram,
+        disk_sampler: Option<DiskSampler>,
     ) -> Self {
         YatpPoolRunner {
             inner,
@@ -161,6 +544,7 @@ impl<T: PoolTicker> YatpPoolRunner<T> {
             before_stop,
             before_pause,
             schedule_wait_duration,
+            disk_sampler,
         }
     }
 }
@@ -176,6 +560,9 @@ pub struct YatpPoolBuilder<T: PoolTicker> {
     max_thread_count: usize,
     stack_size: usize,
     max_tasks: usize,
+    pin_cores: Option<Vec<CoreId>>,
+    numa_aware: bool,
+    disk_check_path: Option<PathBuf>,
 }
 
 impl<T: PoolTicker> YatpPoolBuilder<T> {
@@ -191,9 +578,41 @@ impl<T: PoolTicker> YatpPoolBuilder<T> {
             max_thread_count: 1,
             stack_size: 0,
             max_tasks: std::usize::MAX,
+            pin_cores: None,
+            numa_aware: false,
+            disk_check_path: None,
         }
     }
 
+    /// Hard-pins each worker to one of `cores`, assigned round-robin as
+    /// workers start up. Takes priority over [`Self::numa_aware`] if both
+    /// are set. A no-op on platforms without `sched_setaffinity`.
+    pub fn pin_cores(&mut self, cores: Vec<CoreId>) -> &mut Self {
+        self.pin_cores = Some(cores);
+        self
+    }
+
+    /// Spreads `core_thread_count` workers evenly across detected NUMA
+    /// nodes (round-robin over nodes, not just over cores), so memory
+    /// touched by a worker tends to live on that worker's own node. Ignored
+    /// if [`Self::pin_cores`] is also set. Without NUMA topology information
+    /// the workers are pinned round-robin over cores `0..num_cpus` as if on
+    /// a single node. A no-op on platforms without affinity support.
+    pub fn numa_aware(&mut self, enabled: bool) -> &mut Self {
+        self.numa_aware = enabled;
+        self
+    }
+
+    /// Has this pool sample free space on `path` at the same per-worker tick
+    /// cadence [`TickerWrapper::try_tick`] drives, publishing the result
+    /// through `disk::observe_free_space` so the store-wide `DiskUsage` tier
+    /// stays current even if no other subsystem is polling it. Off by
+    /// default.
+    pub fn disk_aware(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.disk_check_path = Some(path.into());
+        self
+    }
+
     pub fn config(&mut self, config: Config) -> &mut Self {
         // TODO: maybe we should use (1, num_cpu) for min and max thread count.
         self.thread_count(config.workers, config.workers, config.workers)
@@ -278,6 +697,36 @@ impl<T: PoolTicker> YatpPoolBuilder<T> {
             .build_with_queue_and_runner(QueueType::Multilevel(multilevel_builder), runner_builder)
     }
 
+    /// Builds the `after_start` hook the runner actually gets: the
+    /// caller-supplied hook (if any), wrapped so it runs after this
+    /// worker's core pinning, if [`Self::pin_cores`] or [`Self::numa_aware`]
+    /// is set. Each invocation (one per worker thread that starts up) pins
+    /// to the next core in the plan, round-robin.
+    fn build_after_start_with_affinity(&mut self) -> Option<Arc<dyn Fn() + Send + Sync>> {
+        let user_after_start = self.after_start.take();
+        let plan = if let Some(cores) = &self.pin_cores {
+            cores.clone()
+        } else if self.numa_aware {
+            let num_cpus = num_cpus::get();
+            round_robin_over_nodes(&affinity::numa_nodes(num_cpus), self.core_thread_count)
+        } else {
+            return user_after_start;
+        };
+        if plan.is_empty() {
+            return user_after_start;
+        }
+
+        let plan = Arc::new(plan);
+        let next_idx = Arc::new(AtomicUsize::new(0));
+        Some(Arc::new(move || {
+            let idx = next_idx.fetch_add(1, Ordering::Relaxed) % plan.len();
+            let _ = affinity::pin_to_core(plan[idx]);
+            if let Some(f) = user_after_start.as_ref() {
+                f();
+            }
+        }))
+    }
+
     fn create_builder(&mut self) -> (yatp::Builder, YatpPoolRunner<T>) {
         let name = self.name_prefix.as_deref().unwrap_or("yatp_pool");
         let mut builder = yatp::Builder::new(thd_name!(name));
@@ -287,11 +736,12 @@ impl<T: PoolTicker> YatpPoolBuilder<T> {
             .core_thread_count(self.core_thread_count)
             .max_thread_count(self.max_thread_count);
 
-        let after_start = self.after_start.take();
+        let after_start = self.build_after_start_with_affinity();
         let before_stop = self.before_stop.take();
         let before_pause = self.before_pause.take();
         let schedule_wait_duration =
             metrics::YATP_POOL_SCHEDULE_WAIT_DURATION_VEC.with_label_values(&[name]);
+        let disk_sampler = self.disk_check_path.clone().map(DiskSampler::new);
         let read_pool_runner = YatpPoolRunner::new(
             Default::default(),
             self.ticker.clone(),
@@ -299,6 +749,7 @@ impl<T: PoolTicker> YatpPoolBuilder<T> {
             before_stop,
             before_pause,
             schedule_wait_duration,
+            disk_sampler,
         );
         (builder, read_pool_runner)
     }
@@ -338,3 +789,41 @@ mod tests {
         assert_eq!(histogram.get_sample_count() as u32, 6, "{:?}", histogram);
     }
 }
+
+/// Exhaustively explores interleavings of concurrent blocking-pool thread
+/// lifecycle decisions under `--cfg loom` (as Tokio does for its executor).
+/// `YatpPoolRunner`'s own `pause`/`resume`/`end` transitions are driven by
+/// `yatp::pool::Runner`, an external, non-loom-instrumented crate, so they
+/// aren't model-checked here; this instead covers the nearest piece of
+/// concurrent scheduling state this module owns outright -- the
+/// `idle_threads`/`threads` bookkeeping `BlockingPoolInner` uses to decide
+/// whether a newly-queued job needs a fresh worker thread, via the same
+/// `should_spawn` function `submit` calls, run against throwaway atomics
+/// rather than a real pool's `Arc<BlockingPoolInner>`.
+#[cfg(loom)]
+mod loom_tests {
+    use loom::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn concurrent_submit_never_exceeds_max_threads() {
+        loom::model(|| {
+            let idle_threads = Arc::new(BpAtomicUsize::new(0));
+            let threads = Arc::new(Mutex::new(0usize));
+            let max_threads = 2;
+
+            let handles: Vec<_> = (0..3)
+                .map(|_| {
+                    let idle_threads = idle_threads.clone();
+                    let threads = threads.clone();
+                    loom::thread::spawn(move || should_spawn(&idle_threads, &threads, max_threads))
+                })
+                .collect();
+
+            let spawned = handles.into_iter().filter(|h| h.join().unwrap()).count();
+            assert!(spawned <= max_threads);
+            assert!(*threads.lock().unwrap() <= max_threads);
+        });
+    }
+}
@@ -47,6 +47,11 @@ fn test_disk_full() {
     let reserve = 1024 * 1024 * 100;
     let mut cluster = new_server_cluster(0, 3);
     cluster.cfg.storage.reserve_space = ReadableSize(reserve);
+    // Zero grace preserves this test's existing reject-on-sight
+    // assertions; see `disk::wait_for_disk_usage_recovery`, which this
+    // config threads through to the (not-yet-present-here) write
+    // admission path.
+    cluster.cfg.storage.disk_full_grace_duration = ReadableDuration(Duration::from_secs(0));
     cluster.cfg.raft_store.pd_store_heartbeat_tick_interval =
         ReadableDuration(Duration::from_secs(3000)); //disable disk status update influence.
     cluster.run();
@@ -66,6 +71,15 @@ fn test_disk_full() {
     flag = disk::is_disk_full();
     println!("after fail remove, disk full is {:?}", flag);
 
+    // The drain fail-point requests a leader drain on its own, without
+    // changing the tier; once it is removed, no drain is requested while
+    // the tier is below `AlreadyFull`.
+    let disk_full_drain_t = "disk_full_drain_t";
+    fail::cfg(disk_full_drain_t, "return").unwrap();
+    assert!(disk::is_leader_drain_requested());
+    fail::remove(disk_full_drain_t);
+    assert!(!disk::is_leader_drain_requested());
+
     // {
     //     //allow transfer leader
     //     cluster.must_transfer_leader(1, new_peer(1, 1));
@@ -109,3 +123,108 @@ fn test_disk_full() {
     //fail::remove(disk_full_t);
     println!("disk full test over");
 }
+
+// Regression test for a full disk silently stalling stale/follower reads:
+// `must_get` above only exercises the local read fast path, which may not
+// go through the same disk-full gate as a quorum ReadIndex. Force a
+// `read_quorum` request through the router directly so the ReadIndex path
+// is actually covered, and confirm it still succeeds while a concurrent
+// write is rejected.
+#[test]
+fn test_disk_full_allow_read_index() {
+    let reserve = 1024 * 1024 * 100;
+    let mut cluster = new_server_cluster(0, 3);
+    cluster.cfg.storage.reserve_space = ReadableSize(reserve);
+    cluster.cfg.storage.disk_full_grace_duration = ReadableDuration(Duration::from_secs(0));
+    cluster.cfg.raft_store.pd_store_heartbeat_tick_interval =
+        ReadableDuration(Duration::from_secs(3000));
+    cluster.run();
+
+    let must_key = String::from("100").into_bytes();
+    let must_value = String::from("100").into_bytes();
+    cluster.must_put(&must_key, &must_value);
+    cluster.must_get(&must_key).unwrap();
+
+    let region = cluster.get_region(&must_key);
+    let leader = cluster.leader_of_region(region.get_id()).unwrap();
+
+    let disk_full_t = "disk_full_t";
+    fail::cfg(disk_full_t, "return").unwrap();
+
+    // quorum read: forces a real ReadIndex round-trip, not a local lease read.
+    let mut read_req = new_request(
+        region.get_id(),
+        region.get_region_epoch().clone(),
+        vec![new_get_cmd(&must_key)],
+        true,
+    );
+    read_req.mut_header().set_peer(leader.clone());
+    let read_resp = cluster
+        .call_command_on_leader(read_req, Duration::from_secs(5))
+        .unwrap();
+    assert!(
+        !read_resp.get_header().has_error(),
+        "ReadIndex must bypass the disk-full gate: {:?}",
+        read_resp.get_header()
+    );
+    assert_eq!(
+        read_resp.get_responses()[0].get_get().get_value(),
+        must_value.as_slice()
+    );
+
+    // a concurrent write through the same gate must still be rejected.
+    let key_2 = String::from("200").into_bytes();
+    let value_2 = String::from("200").into_bytes();
+    let rx = cluster.async_put(&key_2, &value_2).unwrap();
+    match rx.recv_timeout(Duration::from_secs(10)) {
+        Ok(resp) => assert!(resp.get_header().has_error()),
+        Err(_) => {}
+    }
+
+    fail::remove(disk_full_t);
+}
+
+// A prewrite rejected for `AlreadyFull` must not leave the concurrency
+// manager's max_ts or in-memory lock table touched, or a later
+// async-commit read could observe a barrier with no matching lock behind
+// it. Exercising that requires driving a real prewrite and inspecting
+// `ConcurrencyManager::max_ts`/the lock table afterward, which needs the
+// txn/storage client layer (`Storage::sched_txn_command` or equivalent) --
+// not vendored in this trimmed build, and not something `test_raftstore`'s
+// raw-command cluster API can stand in for. The actual guarantee --
+// `checked_admit`'s mutation closure never runs on a rejected command --
+// is covered directly in `tikv_util::sys::disk`'s
+// `test_checked_admit_rejects_without_advancing_max_ts_or_locking` and
+// `test_checked_admit_advances_max_ts_and_locks_once_admitted`.
+
+// Regression test for emergency reclamation: entering `AlreadyFull` while
+// the disk-full fail-point is active must kick a reclamation pass rather
+// than waiting for the next background tick. The raft-log-gc/compaction
+// jobs `disk::register_emergency_reclamation` is meant to dispatch aren't
+// part of this trimmed build, so this registers its own listener through
+// that same hook and asserts it fires.
+#[test]
+fn test_disk_full_triggers_emergency_reclamation() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    let fired = Arc::new(AtomicBool::new(false));
+    let fired_clone = fired.clone();
+    let _guard = disk::register_emergency_reclamation(Duration::from_secs(0), move || {
+        fired_clone.store(true, Ordering::SeqCst);
+    });
+
+    disk::clear_disk_full();
+    assert!(!fired.load(Ordering::SeqCst));
+
+    let disk_full_t = "disk_full_t";
+    fail::cfg(disk_full_t, "return").unwrap();
+    disk::set_disk_usage(disk::DiskUsage::AlreadyFull);
+    assert!(disk::is_disk_full());
+    assert!(fired.load(Ordering::SeqCst));
+
+    fail::remove(disk_full_t);
+    disk::clear_disk_full();
+}